@@ -0,0 +1,157 @@
+use glam::dvec2;
+
+use crate::Sat;
+
+/// Where recorded clips are written.
+pub const CLIP_PATH: &str = "orbit-clip.csv";
+
+/// The player's state sampled over a stretch of time, for replaying an orbit on its own.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OrbitClip {
+    /// Oldest first. Each `Sat` was sampled at its own `when`.
+    pub samples: Vec<Sat>,
+}
+
+impl OrbitClip {
+    pub fn push(&mut self, sat: Sat) {
+        self.samples.push(sat);
+    }
+
+    /// Seconds from the first sample to the last.
+    pub fn duration(&self) -> f64 {
+        match (self.samples.first(), self.samples.last()) {
+            (Some(first), Some(last)) => last.when - first.when,
+            _ => 0.0,
+        }
+    }
+
+    /// The state at time `t`, or `None` outside the recording.
+    ///
+    /// Position is interpolated as a cubic Hermite spline through the neighboring samples'
+    /// positions and velocities, so a coarse clip still follows the curve of the orbit.
+    pub fn sample_at(&self, t: f64) -> Option<Sat> {
+        let i = self.samples.partition_point(|s| s.when <= t);
+        if i == 0 {
+            return None;
+        }
+        let a = self.samples[i - 1];
+        if i == self.samples.len() {
+            return (a.when == t).then_some(a);
+        }
+        let b = self.samples[i];
+        let dt = b.when - a.when;
+        let s = (t - a.when) / dt;
+        let (s2, s3) = (s * s, s * s * s);
+        let pos = a.pos * (2.0 * s3 - 3.0 * s2 + 1.0)
+            + a.vel * dt * (s3 - 2.0 * s2 + s)
+            + b.pos * (-2.0 * s3 + 3.0 * s2)
+            + b.vel * dt * (s3 - s2);
+        Some(Sat::new(pos, a.vel.lerp(b.vel, s), t))
+    }
+
+    /// One `when,x,y,vx,vy` row per sample, under a header.
+    pub fn to_csv(&self) -> String {
+        let mut ret = String::from("when,x,y,vx,vy\n");
+        for s in &self.samples {
+            ret += &format!(
+                "{},{},{},{},{}\n",
+                s.when, s.pos.x, s.pos.y, s.vel.x, s.vel.y
+            );
+        }
+        ret
+    }
+
+    pub fn from_csv(text: &str) -> Result<Self, String> {
+        let mut clip = Self::default();
+        for (n, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with(|c: char| c.is_ascii_alphabetic()) {
+                continue;
+            }
+            let fields = line
+                .split(',')
+                .map(|f| f.trim().parse::<f64>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| format!("line {}: {e}", n + 1))?;
+            let [when, x, y, vx, vy] = fields[..] else {
+                return Err(format!(
+                    "line {}: expected 5 columns (when,x,y,vx,vy), got {}",
+                    n + 1,
+                    fields.len()
+                ));
+            };
+            if clip.samples.last().is_some_and(|last| last.when >= when) {
+                return Err(format!("line {}: samples must be in time order", n + 1));
+            }
+            clip.push(Sat::new(dvec2(x, y), dvec2(vx, vy), when));
+        }
+        if clip.samples.is_empty() {
+            return Err("clip has no samples".into());
+        }
+        Ok(clip)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Gravity, IntegratorKind, PULL};
+    use glam::DVec2;
+
+    #[test]
+    fn interpolates_between_samples() {
+        // straight line motion is reproduced exactly
+        let mut clip = OrbitClip::default();
+        for i in 0..3 {
+            clip.push(Sat::new(
+                dvec2(i as f64 * 10.0, 5.0),
+                dvec2(10.0, 0.0),
+                i as f64,
+            ));
+        }
+        let mid = clip.sample_at(1.25).unwrap();
+        assert!((mid.pos - dvec2(12.5, 5.0)).length() < 1e-12);
+        assert_eq!(mid.when, 1.25);
+        assert_eq!(clip.sample_at(2.0), clip.samples.last().copied());
+        assert_eq!(clip.sample_at(-0.1), None);
+        assert_eq!(clip.sample_at(2.1), None);
+
+        // an orbit sampled coarsely still lands close to the integrated path between samples
+        let r = 200.0;
+        let mut sat = Sat::new(dvec2(r, 0.0), dvec2(0.0, (PULL / r).sqrt()), 0.0);
+        let mut clip = OrbitClip::default();
+        let mut truth = Vec::new();
+        for i in 1..=20 {
+            clip.push(sat);
+            sat.tick_to(
+                i as f64 * 0.05 - 0.025,
+                IntegratorKind::Verlet,
+                Gravity::Point(PULL),
+            );
+            truth.push(sat);
+            sat.tick_to(
+                i as f64 * 0.05,
+                IntegratorKind::Verlet,
+                Gravity::Point(PULL),
+            );
+        }
+        for want in &truth[..truth.len() - 1] {
+            let got = clip.sample_at(want.when).unwrap();
+            assert!(got.pos.distance(want.pos) < 0.1, "{got:?} {want:?}");
+        }
+    }
+
+    #[test]
+    fn csv_round_trips() {
+        let clip = OrbitClip {
+            samples: vec![
+                Sat::new(dvec2(1.5, -2.0), dvec2(0.25, 3.0), 0.0),
+                Sat::new(DVec2::ZERO, dvec2(1e-9, 7e8), 0.1),
+            ],
+        };
+        assert_eq!(OrbitClip::from_csv(&clip.to_csv()).unwrap(), clip);
+        assert!(OrbitClip::from_csv("when,x,y,vx,vy\n").is_err());
+        assert!(OrbitClip::from_csv("1,0,0,0,0\n0,0,0,0,0").is_err());
+        assert!(OrbitClip::from_csv("0,0,0,0").is_err());
+    }
+}
@@ -0,0 +1,53 @@
+/// Level seed shared by everyone playing on the given (UTC) date.
+pub fn daily_seed(year: i64, month: u32, day: u32) -> u64 {
+    // splitmix64 finalizer, so neighboring dates get unrelated seeds
+    let mut z = (year * 10_000 + month as i64 * 100 + day as i64) as u64;
+    z = z.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// UTC `(year, month, day)` of a unix timestamp.
+pub fn civil_date(unix_seconds: f64) -> (i64, u32, u32) {
+    // Howard Hinnant's days-to-civil algorithm
+    let days = (unix_seconds / 86_400.0).floor() as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let doe = days.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_date_same_seed() {
+        assert_eq!(daily_seed(2024, 2, 29), daily_seed(2024, 2, 29));
+        let seeds = [
+            daily_seed(2024, 2, 29),
+            daily_seed(2024, 3, 1),
+            daily_seed(2025, 2, 29),
+            daily_seed(2024, 2, 28),
+        ];
+        for (i, a) in seeds.iter().enumerate() {
+            for b in &seeds[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn civil_dates() {
+        assert_eq!(civil_date(0.0), (1970, 1, 1));
+        assert_eq!(civil_date(1_700_000_000.0), (2023, 11, 14));
+        assert_eq!(civil_date(951_782_400.0), (2000, 2, 29));
+        assert_eq!(civil_date(-1.0), (1969, 12, 31));
+    }
+}
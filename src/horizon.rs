@@ -0,0 +1,173 @@
+use glam::DVec2;
+
+use crate::{eccentricity_vector, Sat};
+
+/// Eccentricities this close to 1 are too ill-conditioned for the anomaly formulas below.
+pub const NEAR_PARABOLIC: f64 = 1e-6;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Crossing {
+    Never,
+    /// Seconds from now. Zero if already inside.
+    After(f64),
+    /// Nearly parabolic or radial, where the conic formulas break down. Check numerically.
+    Unsolvable,
+}
+
+/// When `sat`, coasting around a single central body, first reaches `horizon` distance from it.
+///
+/// Solved exactly on the conic rather than by stepping, so a fast periapsis pass can't skip over
+/// the horizon between steps.
+pub fn crossing_time(sat: &Sat, mu: f64, horizon: f64) -> Crossing {
+    let r = sat.pos.length();
+    if r <= horizon {
+        return Crossing::After(0.0);
+    }
+    let h = sat.pos.perp_dot(sat.vel);
+    let e_vec = eccentricity_vector(sat.pos, sat.vel, mu);
+    let e = e_vec.length();
+    let p = h * h / mu;
+    if (e - 1.0).abs() < NEAR_PARABOLIC || p < f64::EPSILON * r {
+        return Crossing::Unsolvable;
+    }
+    if p / (1.0 + e) >= horizon {
+        // periapsis clears the horizon
+        return Crossing::Never;
+    }
+
+    let nu = true_anomaly(sat.pos, sat.vel, e_vec);
+    // the crossing on the way in, before periapsis
+    let nu_cross = -((p / horizon - 1.0) / e).clamp(-1.0, 1.0).acos();
+    let t_cross = time_since_periapsis(nu_cross, e, p, mu);
+    let t_now = time_since_periapsis(nu, e, p, mu);
+    if nu <= nu_cross {
+        return Crossing::After(t_cross - t_now);
+    }
+    // already past the crossing on the way out; only a closed orbit comes back around
+    if e < 1.0 {
+        let a = p / (1.0 - e * e);
+        let period = std::f64::consts::TAU * (a * a * a / mu).sqrt();
+        Crossing::After(period + t_cross - t_now)
+    } else {
+        Crossing::Never
+    }
+}
+
+/// Angle from periapsis in the direction of motion, in `(-pi, pi]`.
+pub fn true_anomaly(pos: DVec2, vel: DVec2, e_vec: DVec2) -> f64 {
+    let Some(e_dir) = e_vec.try_normalize() else {
+        // circular, so measure from wherever we are
+        return 0.0;
+    };
+    let angle = e_dir.dot(pos.normalize()).clamp(-1.0, 1.0).acos();
+    if pos.dot(vel) < 0.0 {
+        -angle
+    } else {
+        angle
+    }
+}
+
+/// Signed time from periapsis to true anomaly `nu` on a non-parabolic conic.
+pub fn time_since_periapsis(nu: f64, e: f64, p: f64, mu: f64) -> f64 {
+    let half = (nu / 2.0).tan();
+    if e < 1.0 {
+        let a = p / (1.0 - e * e);
+        let ecc_anomaly = 2.0 * (((1.0 - e) / (1.0 + e)).sqrt() * half).atan();
+        let mean_anomaly = ecc_anomaly - e * ecc_anomaly.sin();
+        mean_anomaly * (a * a * a / mu).sqrt()
+    } else {
+        let a = p / (e * e - 1.0);
+        let hyp_anomaly = 2.0 * (((e - 1.0) / (e + 1.0)).sqrt() * half).atanh();
+        let mean_anomaly = e * hyp_anomaly.sinh() - hyp_anomaly;
+        mean_anomaly * (a * a * a / mu).sqrt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Gravity, IntegratorKind, PULL};
+    use glam::dvec2;
+
+    /// First time a finely stepped `sat` is within `horizon`, if it is within `limit` seconds.
+    fn numeric_crossing(sat: Sat, horizon: f64, limit: f64) -> Option<f64> {
+        let dt = 1e-6;
+        let mut sat = sat;
+        let start = sat.when;
+        while sat.when < start + limit {
+            if sat.pos.length() <= horizon {
+                return Some(sat.when - start);
+            }
+            sat.step(dt, IntegratorKind::Verlet, Gravity::Point(PULL));
+        }
+        None
+    }
+
+    fn analytic_crossing(sat: &Sat, horizon: f64) -> f64 {
+        match crossing_time(sat, PULL, horizon) {
+            Crossing::After(t) => t,
+            other => panic!("{other:?}"),
+        }
+    }
+
+    fn orbit(apoapsis: f64, periapsis: f64) -> Sat {
+        // start at apoapsis, moving counterclockwise
+        let a = (apoapsis + periapsis) / 2.0;
+        let speed = (PULL * (2.0 / apoapsis - 1.0 / a)).sqrt();
+        Sat::new(dvec2(-apoapsis, 0.0), dvec2(0.0, -speed), 0.0)
+    }
+
+    #[test]
+    fn grazing_orbit_matches_numeric() {
+        let horizon = 50.0;
+        let sat = orbit(200.0, 49.0);
+        let analytic = analytic_crossing(&sat, horizon);
+        let numeric = numeric_crossing(sat, horizon, 1.0).unwrap();
+        assert!((analytic - numeric).abs() < 1e-4, "{analytic} {numeric}");
+
+        // the same orbit, flown clockwise from partway round
+        let mut sat = Sat {
+            vel: -sat.vel,
+            ..sat
+        };
+        sat.tick_to(0.05, IntegratorKind::Verlet, Gravity::Point(PULL));
+        let analytic = analytic_crossing(&sat, horizon);
+        let numeric = numeric_crossing(sat, horizon, 1.0).unwrap();
+        assert!((analytic - numeric).abs() < 1e-4, "{analytic} {numeric}");
+    }
+
+    #[test]
+    fn outbound_ellipse_comes_back_around() {
+        // start on the way back out to apoapsis, after having passed through the horizon
+        let horizon = 50.0;
+        let mut sat = orbit(200.0, 45.0);
+        let first = analytic_crossing(&sat, horizon);
+        sat.tick_to(2.0 * first, IntegratorKind::Verlet, Gravity::Point(PULL));
+        assert!(sat.pos.length() > horizon && sat.pos.dot(sat.vel) > 0.0);
+
+        let analytic = analytic_crossing(&sat, horizon);
+        let numeric = numeric_crossing(sat, horizon, 3.0).unwrap();
+        assert!((analytic - numeric).abs() < 1e-3, "{analytic} {numeric}");
+    }
+
+    #[test]
+    fn clear_orbits_never_cross() {
+        assert_eq!(
+            crossing_time(&orbit(200.0, 51.0), PULL, 50.0),
+            Crossing::Never
+        );
+
+        let escaping = Sat::new(
+            dvec2(100.0, 0.0),
+            dvec2(0.0, 2.0 * (PULL / 100.0).sqrt()),
+            0.0,
+        );
+        assert_eq!(crossing_time(&escaping, PULL, 50.0), Crossing::Never);
+
+        let inside = Sat {
+            pos: dvec2(10.0, 0.0),
+            ..escaping
+        };
+        assert_eq!(crossing_time(&inside, PULL, 50.0), Crossing::After(0.0));
+    }
+}
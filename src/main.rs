@@ -1,191 +1,2653 @@
+mod clip;
+mod daily;
+mod horizon;
+mod monitor;
+mod particles;
+mod shadow;
+mod state_import;
+mod summary;
+mod theme;
+mod trail;
+
 use std::ops::Range;
 
-use glam::{dvec2, DMat3, DVec2, Vec3Swizzles};
+use clip::{OrbitClip, CLIP_PATH};
+use glam::{dvec2, DMat3, DVec2};
+use horizon::Crossing;
 use itertools::Itertools;
 use macroquad::{
-    color::colors::{self, BEIGE, DARKBLUE, DARKBROWN, MAROON},
+    miniquad,
     prelude::{
-        clear_background, draw_circle, draw_line, get_time, is_mouse_button_pressed,
-        mouse_position, next_frame, screen_height, screen_width, vec2, Color, MouseButton, YELLOW,
+        clear_background, draw_circle, draw_circle_lines, draw_line, draw_text, draw_triangle,
+        get_frame_time, get_time, is_key_down, is_key_pressed, is_mouse_button_pressed,
+        is_mouse_button_released, mouse_position, next_frame, screen_height, screen_width, vec2,
+        Color, KeyCode, MouseButton,
     },
-    rand::gen_range,
+    rand::{gen_range, srand},
     shapes::draw_rectangle,
 };
+use monitor::{GForceMonitor, OrbitStats, SpeedHistory};
+use particles::ParticleSystem;
+use summary::{RunSummary, SUMMARY_PATH};
+use theme::ThemeColors;
+use trail::{OrbitCounter, Trail};
 
 const WORLD_RADIUS_METERS: f64 = 1024.0;
 const BLACK_HOLE_MASS: f64 = 5.97219_e17;
 const GRAVITATIONAL_CONSTANT: f64 = 6.67_e-11;
 const PULL: f64 = BLACK_HOLE_MASS * GRAVITATIONAL_CONSTANT;
+const SPEED_OF_LIGHT: f64 = 299_792_458.0;
+/// Radius of the black hole as drawn and as far as gameplay is concerned. Much larger than the
+/// real Schwarzschild radius so it can be seen.
+const EVENT_HORIZON_METERS: f64 = 50.0;
+const THEME_PATH: &str = "theme.txt";
+/// Seconds the emergency warp takes to recharge after use.
+const EJECT_COOLDOWN: f64 = 30.0;
+/// Most test particles the sandbox holds at once.
+const MAX_TEST_PARTICLES: usize = 16;
+/// Speed, in m/s, a dropped test particle gets for each meter it is dragged.
+const DROP_SPEED_PER_METER: f64 = 4.0;
+/// Most orbits that can be pinned at once.
+const MAX_PINNED: usize = 6;
+/// Longest orbit clip recorded before it is saved automatically.
+const CLIP_SECONDS: f64 = 10.0;
+
+struct Config {
+    /// How close, in world meters, the cursor must be to a warp point to select it.
+    capture_radius: f64,
+    /// Draw a faint ring around each warp point at `capture_radius`.
+    show_capture_radius: bool,
+    integrator: IntegratorKind,
+    /// Debug overlay of the player's eccentricity and angular momentum vectors.
+    show_orbit_vectors: bool,
+    show_apsis_line: bool,
+    /// Draw the physical event horizon, which is far smaller than the one drawn for gameplay.
+    show_schwarzschild_radius: bool,
+    /// Slow cosmetic animations as the player nears the horizon. Physics is unaffected.
+    time_dilation_effect: bool,
+    /// Draw where the player has been, colored by which orbit they were on.
+    show_trail: bool,
+    /// Acceleration, in g, that tears the ship apart.
+    max_g: Option<f64>,
+    /// Debug overlay of the path integrated over the last frame, next to the straight line
+    /// between its ends.
+    show_substeps: bool,
+    /// Distance from the black hole the player starts at, in meters.
+    start_radius: f64,
+    /// Starting speed as a fraction of circular orbit speed.
+    start_speed: f64,
+    /// Level seed to use instead of the daily one.
+    seed: Option<u64>,
+    /// Start by launching off the event horizon instead of on the starting orbit.
+    launch: Option<Launch>,
+    /// Draw distances from the black hole on a log scale past this many meters, so the horizon
+    /// and far orbits are visible together.
+    log_radial_scale: Option<f64>,
+    /// Also print the end-of-run summary to stdout.
+    print_run_summary: bool,
+    /// Graph the player's recent speed.
+    show_speed_graph: bool,
+    /// Shed particles behind the player, which stretch out with speed.
+    show_comet_tail: bool,
+    /// Right click and drag to drop test particles that orbit alongside the player.
+    sandbox: bool,
+    /// Switch gravity off, so the player and test particles coast in straight lines.
+    no_gravity: bool,
+    /// Mark where the player would be without integrator error, and how far off they are.
+    show_position_error: bool,
+    /// List what each color on screen means.
+    show_legend: bool,
+    /// From 0, a raw position swap, to 1, where any click warps to the nearest point and the
+    /// velocity is adjusted to keep the orbit's energy without hitting the horizon.
+    warp_assist: f64,
+    /// Velocity is multiplied by this on every warp, so repeated warps bleed energy. 1.0 keeps
+    /// it all.
+    warp_damping: f64,
+    /// Stop drawing the predicted path once it strays this far (in world meters, per axis)
+    /// from the black hole.
+    max_prediction_distance: Option<f64>,
+    theme: ThemeColors,
+    show_hud: bool,
+    /// Horizontal stretch of the world relative to vertical. Anything but 1.0 draws circles as
+    /// ellipses.
+    world_aspect: f64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            capture_radius: 128.0,
+            show_capture_radius: true,
+            integrator: IntegratorKind::Euler,
+            show_orbit_vectors: false,
+            show_apsis_line: false,
+            show_schwarzschild_radius: false,
+            time_dilation_effect: false,
+            show_trail: false,
+            max_g: None,
+            show_substeps: false,
+            start_radius: WORLD_RADIUS_METERS / 6.0,
+            start_speed: 0.8,
+            seed: None,
+            launch: None,
+            log_radial_scale: None,
+            print_run_summary: false,
+            show_speed_graph: false,
+            show_comet_tail: false,
+            sandbox: false,
+            no_gravity: false,
+            show_position_error: false,
+            show_legend: false,
+            warp_assist: 0.0,
+            warp_damping: 1.0,
+            max_prediction_distance: Some(WORLD_RADIUS_METERS * 1.5),
+            theme: ThemeColors::default(),
+            show_hud: true,
+            world_aspect: 1.0,
+        }
+    }
+}
+
+impl Config {
+    /// How close the cursor must be to a warp point to select it. Full warp assist forgives
+    /// missing the warp point entirely.
+    fn selection_radius(&self) -> f64 {
+        if self.warp_assist >= 1.0 {
+            f64::INFINITY
+        } else {
+            self.capture_radius
+        }
+    }
+
+    fn gravity(&self) -> Gravity {
+        if self.no_gravity {
+            Gravity::Off
+        } else {
+            Gravity::Point(PULL)
+        }
+    }
+}
 
 struct Player {
     sat: Sat,
 }
 
-#[derive(Clone, Copy, Debug)]
+/// What pulls on everything being integrated.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Gravity {
+    /// Inverse-square pull toward the origin, with this gravitational parameter.
+    Point(f64),
+    /// Nothing, so everything coasts in straight lines.
+    Off,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum IntegratorKind {
+    /// Semi-implicit (symplectic) Euler: update velocity, then position using the new velocity.
+    Euler,
+    /// Velocity Verlet.
+    Verlet,
+}
+
+impl IntegratorKind {
+    fn next(self) -> Self {
+        match self {
+            Self::Euler => Self::Verlet,
+            Self::Verlet => Self::Euler,
+        }
+    }
+}
+
+/// A start from just above the event horizon.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Launch {
+    /// Radians above the local horizontal, so pi/2 is straight up.
+    angle: f64,
+    /// Multiple of circular orbit speed at the launch radius.
+    speed: f64,
+}
+
+impl Launch {
+    /// Launch from this far out, as the horizon check counts anything at the horizon as fallen in.
+    const RADIUS: f64 = EVENT_HORIZON_METERS + 1.0;
+
+    /// The launch state. Fails if the ship would come back down below the launch radius.
+    fn orbit(&self, mu: f64) -> Result<Sat, String> {
+        let pos = dvec2(Self::RADIUS, 0.0);
+        let v_mag = self.speed * (mu / Self::RADIUS).sqrt();
+        // launching from the +x axis, where up is +x and horizontal is +y
+        let vel = dvec2(self.angle.sin(), self.angle.cos()) * v_mag;
+        let sat = Sat::new(pos, vel, 0.0);
+        let escaping = sat.specific_energy(mu) >= 0.0 && sat.pos.dot(sat.vel) >= 0.0;
+        let periapsis = sat.periapsis(mu).length();
+        // allow for rounding when launching horizontally from periapsis
+        if !escaping && (periapsis.is_nan() || periapsis < Self::RADIUS * (1.0 - 1e-9)) {
+            return Err(format!(
+                "launch at {:.1} rad, {} times circular speed falls back to {periapsis:.1} m, \
+                 below the {} m launch radius",
+                self.angle,
+                self.speed,
+                Self::RADIUS
+            ));
+        }
+        Ok(sat)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
 struct Sat {
     pos: DVec2,
     vel: DVec2,
     // when were we at this pos and vel
     when: f64,
+    /// Keeps the rounding error in `when` between calls to `tick_to`, with the
+    /// `compensated-time` feature. Restarted from `when` whenever the two disagree.
+    clock: CompensatedSum,
 }
 
+/// Fixed simulation step, in seconds.
+const TICK: f64 = 0.001;
+
 impl Sat {
-    fn tick_to(&mut self, when: f64) {
-        let dt = 0.001;
+    fn new(pos: DVec2, vel: DVec2, when: f64) -> Self {
+        Self {
+            pos,
+            vel,
+            when,
+            clock: CompensatedSum::new(when),
+        }
+    }
+
+    fn tick_to(&mut self, when: f64, integrator: IntegratorKind, gravity: Gravity) {
+        self.tick_to_with(when, integrator, gravity, |_| {});
+    }
+
+    /// Like `tick_to`, but calls `on_step` with the state after every substep.
+    fn tick_to_with(
+        &mut self,
+        when: f64,
+        integrator: IntegratorKind,
+        gravity: Gravity,
+        mut on_step: impl FnMut(&Sat),
+    ) {
+        if cfg!(feature = "compensated-time") && self.clock.total() != self.when {
+            self.clock = CompensatedSum::new(self.when);
+        }
         while self.when < when {
-            let acc = self.acceleration();
-            self.vel += acc * dt;
-            self.pos += self.vel * dt;
-            self.when += dt;
+            self.step(TICK, integrator, gravity);
+            if cfg!(feature = "compensated-time") {
+                self.clock.add(TICK);
+                self.when = self.clock.total();
+            }
+            on_step(self);
+        }
+    }
+
+    fn step(&mut self, dt: f64, integrator: IntegratorKind, gravity: Gravity) {
+        match integrator {
+            IntegratorKind::Euler => {
+                let acc = self.acceleration(gravity);
+                self.vel += acc * dt;
+                self.pos += self.vel * dt;
+            }
+            IntegratorKind::Verlet => {
+                let acc = self.acceleration(gravity);
+                self.pos += self.vel * dt + acc * (0.5 * dt * dt);
+                let new_acc = self.acceleration(gravity);
+                self.vel += (acc + new_acc) * (0.5 * dt);
+            }
+        }
+        self.when += dt;
+    }
+
+    /// The orbit the player starts on, `radius` meters out at `speed_scale` times circular
+    /// speed. Fails if it would dip into the event horizon.
+    fn initial_orbit(radius: f64, speed_scale: f64, mu: f64) -> Result<Self, String> {
+        let pos = dvec2(radius, 0.0);
+        let v_mag = (mu / pos.length()).sqrt();
+        let sat = Sat::new(pos, dvec2(0.0, speed_scale * v_mag), 0.0);
+        let periapsis = sat.periapsis(mu).length();
+        if periapsis.is_nan() || periapsis <= EVENT_HORIZON_METERS {
+            return Err(format!(
+                "starting orbit reaches {periapsis:.1} m from the black hole, inside the \
+                 {EVENT_HORIZON_METERS} m event horizon"
+            ));
         }
+        Ok(sat)
     }
 
-    fn acceleration(&self) -> DVec2 {
+    /// Kinetic plus potential energy per unit mass. Negative for bound orbits.
+    fn specific_energy(&self, mu: f64) -> f64 {
+        self.vel.length_squared() / 2.0 - mu / self.pos.length()
+    }
+
+    /// Radians above the local horizontal: positive heading away from the black hole, zero at
+    /// the apsides.
+    fn flight_path_angle(&self) -> f64 {
         let r = self.pos.length();
-        let r3 = r * r * r;
-        -self.pos * PULL / r3
+        let radial = self.pos.dot(self.vel) / r;
+        let tangential = self.pos.perp_dot(self.vel).abs() / r;
+        radial.atan2(tangential)
+    }
+
+    fn eccentricity(&self, mu: f64) -> f64 {
+        let h = self.pos.perp_dot(self.vel);
+        (1.0 + 2.0 * self.specific_energy(mu) * h * h / (mu * mu))
+            .max(0.0)
+            .sqrt()
+    }
+
+    /// Negative for hyperbolic orbits, infinite for parabolic ones.
+    fn semi_major_axis(&self, mu: f64) -> f64 {
+        -mu / (2.0 * self.specific_energy(mu))
+    }
+
+    /// Position of closest approach to the black hole.
+    fn periapsis(&self, mu: f64) -> DVec2 {
+        let h = self.pos.perp_dot(self.vel);
+        let e = eccentricity_vector(self.pos, self.vel, mu);
+        // a circular orbit has no preferred periapsis, so anywhere on it will do
+        let dir = e.try_normalize().unwrap_or_else(|| self.pos.normalize());
+        dir * h * h / (mu * (1.0 + e.length()))
+    }
+
+    /// Position of furthest distance from the black hole, if the orbit is bound.
+    fn apoapsis(&self, mu: f64) -> Option<DVec2> {
+        let h = self.pos.perp_dot(self.vel);
+        let e = eccentricity_vector(self.pos, self.vel, mu);
+        if e.length() >= 1.0 {
+            return None;
+        }
+        let dir = e.try_normalize().unwrap_or_else(|| self.pos.normalize());
+        Some(-dir * h * h / (mu * (1.0 - e.length())))
+    }
+
+    fn acceleration(&self, gravity: Gravity) -> DVec2 {
+        match gravity {
+            Gravity::Point(mu) => {
+                // zero or negative mu would quietly stop or reverse the pull; `Off` says so instead
+                debug_assert!(
+                    mu > 0.0,
+                    "gravitational parameter must be positive, got {mu}"
+                );
+                let r = self.pos.length();
+                let r3 = r * r * r;
+                -self.pos * mu / r3
+            }
+            Gravity::Off => DVec2::ZERO,
+        }
     }
 }
 
-#[derive(Clone)]
-struct WarpPoint {
-    pos: DVec2,
+/// Points from the focus toward periapsis, with length equal to the orbit's eccentricity.
+fn eccentricity_vector(pos: DVec2, vel: DVec2, mu: f64) -> DVec2 {
+    ((vel.length_squared() - mu / pos.length()) * pos - pos.dot(vel) * vel) / mu
+}
+
+/// Specific orbital energy in J/kg, and relative to the magnitude of a circular orbit's energy
+/// at radius `r`: -1 when circular there, 0 at escape.
+fn format_energy(energy: f64, r: f64, mu: f64) -> String {
+    let circular = mu / (2.0 * r);
+    format!(
+        "energy {energy:.3e} J/kg ({:.2} circular)",
+        energy / circular
+    )
+}
+
+/// Maps world meters to screen pixels, fitting the world radius vertically into the smaller
+/// screen dimension and putting `center` in the middle of the screen. With `log_radial`, space
+/// is first compressed radially around the black hole (see `log_radial`).
+fn build_view(screen: DVec2, world_aspect: f64, center: DVec2, log_radial: Option<f64>) -> View {
+    let scale = screen.min_element() / 2.0 / WORLD_RADIUS_METERS;
+    let center = log_radial.map_or(center, |r0| self::log_radial(center, r0));
+    View {
+        affine: DMat3::from_translation(screen / 2.0)
+            * DMat3::from_scale(dvec2(scale * world_aspect, scale))
+            * DMat3::from_translation(-center),
+        log_radial,
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct View {
+    affine: DMat3,
+    log_radial: Option<f64>,
+}
+
+impl View {
+    fn project(&self, pos: DVec2) -> DVec2 {
+        match self.log_radial {
+            Some(r0) => world_to_screen_log(pos, &self.affine, r0),
+            None => self.affine.transform_point2(pos),
+        }
+    }
+
+    fn unproject(&self, pos: DVec2) -> DVec2 {
+        match self.log_radial {
+            Some(r0) => screen_to_world_log(pos, &self.affine, r0),
+            None => self.affine.inverse().transform_point2(pos),
+        }
+    }
+
+    /// The same view magnified by `zoom` about the middle of `screen`.
+    fn zoomed(self, screen: DVec2, zoom: f64) -> Self {
+        let middle = screen / 2.0;
+        Self {
+            affine: DMat3::from_translation(middle)
+                * DMat3::from_scale(DVec2::splat(zoom))
+                * DMat3::from_translation(-middle)
+                * self.affine,
+            ..self
+        }
+    }
+}
+
+/// Simulation time, which follows the wall clock except while paused.
+#[derive(Clone, Copy, Debug, Default)]
+struct SimClock {
+    /// Simulation time the clock stopped at.
+    paused_at: Option<f64>,
+    /// Wall clock seconds spent paused, so the simulation picks up where it left off.
+    paused_for: f64,
+}
+
+impl SimClock {
+    fn now(&self, wall: f64) -> f64 {
+        self.paused_at.unwrap_or(wall - self.paused_for)
+    }
+
+    fn paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+
+    fn pause(&mut self, wall: f64) {
+        if !self.paused() {
+            self.paused_at = Some(self.now(wall));
+        }
+    }
+
+    fn resume(&mut self, wall: f64) {
+        if let Some(at) = self.paused_at.take() {
+            self.paused_for = wall - at;
+        }
+    }
+
+    /// Move the stopped clock on to `to`, for stepping while paused.
+    fn advance_paused(&mut self, to: f64) {
+        if self.paused() {
+            self.paused_at = Some(to);
+        }
+    }
+}
+
+/// A free camera over a frozen simulation, for composing screenshots.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct PhotoMode {
+    center: DVec2,
+    zoom: f64,
+    /// Hide the HUD, speed graph, legend, and debug overlays, leaving the player, warp points,
+    /// and prediction.
+    hide_overlays: bool,
+    /// Whether the simulation was running before, so leaving photo mode should restart it.
+    resume_on_exit: bool,
+}
+
+impl PhotoMode {
+    fn enter(center: DVec2, was_paused: bool) -> Self {
+        Self {
+            center,
+            zoom: 1.0,
+            hide_overlays: true,
+            resume_on_exit: !was_paused,
+        }
+    }
+
+    fn pan(&mut self, by: DVec2) {
+        self.center += by;
+    }
+
+    fn zoom_by(&mut self, factor: f64) {
+        self.zoom = (self.zoom * factor).clamp(0.1, 100.0);
+    }
+}
+
+/// Compress distances from the black hole logarithmically beyond about `r0`, leaving the world
+/// radius where it is, so the horizon and the edge of the world are both visible at once.
+fn log_radial(pos: DVec2, r0: f64) -> DVec2 {
+    let r = pos.length();
+    if r == 0.0 {
+        return pos;
+    }
+    let warped = WORLD_RADIUS_METERS * (r / r0).ln_1p() / (WORLD_RADIUS_METERS / r0).ln_1p();
+    pos * (warped / r)
+}
+
+fn log_radial_inverse(pos: DVec2, r0: f64) -> DVec2 {
+    let warped = pos.length();
+    if warped == 0.0 {
+        return pos;
+    }
+    let r = r0 * (warped / WORLD_RADIUS_METERS * (WORLD_RADIUS_METERS / r0).ln_1p()).exp_m1();
+    pos * (r / warped)
+}
+
+fn world_to_screen_log(pos: DVec2, affine: &DMat3, r0: f64) -> DVec2 {
+    affine.transform_point2(log_radial(pos, r0))
+}
+
+fn screen_to_world_log(pos: DVec2, affine: &DMat3, r0: f64) -> DVec2 {
+    log_radial_inverse(affine.inverse().transform_point2(pos), r0)
+}
+
+/// Next warp point to focus on, wrapping back to the first after the last.
+fn cycle_focus(focused: Option<usize>, len: usize) -> Option<usize> {
+    if len == 0 {
+        return None;
+    }
+    Some(focused.map_or(0, |i| (i + 1) % len))
+}
+
+/// Closed screen-space outline of a world-space circle.
+fn world_circle_outline(center: DVec2, radius: f64, world_to_screen: &View) -> Vec<DVec2> {
+    let segments = 48;
+    (0..=segments)
+        .map(|i| {
+            let angle = std::f64::consts::TAU * i as f64 / segments as f64;
+            world_to_screen.project(center + DVec2::from_angle(angle) * radius)
+        })
+        .collect()
+}
+
+/// Fill a world-space circle, so it keeps its true extent when the view is stretched.
+fn draw_world_disc(center: DVec2, radius: f64, world_to_screen: &View, color: Color) {
+    let to_vec2 = |p: DVec2| vec2(p.x as f32, p.y as f32);
+    let middle = to_vec2(world_to_screen.project(center));
+    for (a, b) in world_circle_outline(center, radius, world_to_screen)
+        .into_iter()
+        .tuple_windows()
+    {
+        draw_triangle(middle, to_vec2(a), to_vec2(b), color);
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Rect {
+    min: DVec2,
+    max: DVec2,
+}
+
+impl Rect {
+    fn contains(&self, p: DVec2) -> bool {
+        p.cmpge(self.min).all() && p.cmple(self.max).all()
+    }
+}
+
+/// The leading part of `points` that lies within `bounds`. If the polyline leaves `bounds`, it
+/// is cut where it first crosses the edge.
+fn clip_polyline(points: &[DVec2], bounds: Rect) -> Vec<DVec2> {
+    let mut ret = Vec::new();
+    for &b in points {
+        if bounds.contains(b) {
+            ret.push(b);
+            continue;
+        }
+        if let Some(&a) = ret.last() {
+            // fraction of the way from a to b at which an axis leaves bounds
+            let exit = |a: f64, b: f64, min: f64, max: f64| {
+                if b > max {
+                    (max - a) / (b - a)
+                } else if b < min {
+                    (min - a) / (b - a)
+                } else {
+                    1.0
+                }
+            };
+            let tx = exit(a.x, b.x, bounds.min.x, bounds.max.x);
+            let ty = exit(a.y, b.y, bounds.min.y, bounds.max.y);
+            ret.push(a.lerp(b, tx.min(ty)));
+        }
+        break;
+    }
+    ret
+}
+
+fn schwarzschild_radius(mass: f64) -> f64 {
+    2.0 * GRAVITATIONAL_CONSTANT * mass / (SPEED_OF_LIGHT * SPEED_OF_LIGHT)
+}
+
+/// Rate at which cosmetic animations run at `altitude` above the horizon, relative to far away.
+/// Shaped like gravitational time dilation: approaches 0 at the horizon and 1 far from it.
+fn dilation_factor(altitude: f64, horizon: f64) -> f64 {
+    let altitude = altitude.max(0.0);
+    (altitude / (altitude + horizon)).sqrt().max(1e-3)
+}
+
+/// Ends of the line of apsides. Unbound orbits have no apoapsis, so only the periapsis is given.
+fn apsis_line(sat: &Sat, mu: f64) -> (DVec2, Option<DVec2>) {
+    (sat.periapsis(mu), sat.apoapsis(mu))
+}
+
+/// Points around the whole conic `sat` is on, leaving out any of it beyond `max_radius`.
+fn orbit_polyline(sat: &Sat, mu: f64, points: usize, max_radius: f64) -> Vec<DVec2> {
+    let h = sat.pos.perp_dot(sat.vel);
+    let e_vec = eccentricity_vector(sat.pos, sat.vel, mu);
+    let e = e_vec.length();
+    let p = h * h / mu;
+    let periapsis_dir = e_vec.try_normalize().unwrap_or_else(|| sat.pos.normalize());
+    let last = (points - 1) as f64;
+    (0..points)
+        .map(|i| (i as f64).remap(0.0..last, -std::f64::consts::PI..std::f64::consts::PI))
+        .map(|nu| (nu, p / (1.0 + e * nu.cos())))
+        // open orbits only reach out to their asymptotes, past which r goes negative
+        .filter(|&(_, r)| r > 0.0 && r <= max_radius)
+        .map(|(nu, r)| DVec2::from_angle(nu).rotate(periapsis_dir) * r)
+        .collect()
+}
+
+/// An orbit kept on screen for comparison while the player maneuvers.
+#[derive(Clone, Debug, PartialEq)]
+struct PinnedOrbit {
+    sat: Sat,
+    path: Vec<DVec2>,
+    /// Chosen when pinned, so forgetting older pins doesn't recolor the rest.
     color: Color,
-    win_destination: DVec2,
 }
 
-#[macroquad::main("ftl-hole")]
-async fn main() {
-    // create 4 warp points with random positions and destinations
-    let mut warp_points = [DARKBROWN, MAROON, DARKBLUE, BEIGE].map(|color| WarpPoint {
+/// Pin the orbit `sat` is on, forgetting the oldest pin if there are already `MAX_PINNED`. The
+/// pin gets the first color in `palette` no other pin is using.
+fn pin_orbit(pins: &mut Vec<PinnedOrbit>, sat: Sat, mu: f64, palette: &[Color]) {
+    if pins.len() == MAX_PINNED {
+        pins.remove(0);
+    }
+    let color = palette
+        .iter()
+        .copied()
+        .find(|c| pins.iter().all(|p| p.color != *c))
+        .unwrap_or(palette[pins.len() % palette.len()]);
+    pins.push(PinnedOrbit {
+        sat,
+        path: orbit_polyline(&sat, mu, 256, 4.0 * WORLD_RADIUS_METERS),
         color,
-        pos: dvec2(gen_range(-1.0, 1.0), gen_range(-1.0, 1.0)).normalize() * WORLD_RADIUS_METERS,
-        win_destination: dvec2(gen_range(-1.0, 1.0), gen_range(-1.0, 1.0)).normalize()
-            * WORLD_RADIUS_METERS,
     });
+}
 
-    let initial_radius: f32 = 1.0 / 6.0;
-    let pos = dvec2(initial_radius as f64, 0.0) * WORLD_RADIUS_METERS;
-    let v_scale = 0.8;
-    let v_mag = (PULL / pos.length()).sqrt();
-    let mut player = Player {
-        sat: Sat {
-            pos,
-            vel: dvec2(0.0, v_scale * v_mag),
-            when: 0.0,
-        },
-    };
+/// Fewest and most samples drawn along a predicted path.
+const PREDICTION_MIN_POINTS: usize = 16;
+const PREDICTION_MAX_POINTS: usize = 256;
+/// On-screen spacing, in pixels, of predicted path samples between those limits.
+const PREDICTION_SPACING_PX: f64 = 8.0;
 
-    loop {
-        let time = get_time();
+/// How many samples to draw along a path `screen_arc_px` long on screen.
+fn adaptive_sample_count(screen_arc_px: f64, min: usize, max: usize) -> usize {
+    let spans = (screen_arc_px / PREDICTION_SPACING_PX).ceil().max(0.0) as usize;
+    spans.saturating_add(1).clamp(min, max)
+}
 
-        let screen_min_dim = screen_width().min(screen_height()) as f64;
+/// `predicted_path` thinned to just enough samples to look smooth at the current zoom.
+fn predicted_path_for_view(
+    sat: Sat,
+    view: &View,
+    integrator: IntegratorKind,
+    gravity: Gravity,
+    max_distance: Option<f64>,
+) -> Vec<DVec2> {
+    let path = predicted_path(sat, integrator, gravity, max_distance);
+    let arc = path
+        .iter()
+        .map(|p| view.project(*p))
+        .tuple_windows()
+        .map(|(a, b)| a.distance(b))
+        .sum();
+    let n = adaptive_sample_count(arc, PREDICTION_MIN_POINTS, PREDICTION_MAX_POINTS);
+    thin_polyline(&path, n)
+}
 
-        let world_to_screen = DMat3::from_translation(dvec2(
-            screen_width() as f64 / 2.0,
-            screen_height() as f64 / 2.0,
-        )) * DMat3::from_scale(dvec2(
-            screen_min_dim / 2.0 / WORLD_RADIUS_METERS,
-            screen_min_dim / 2.0 / WORLD_RADIUS_METERS,
-        ));
-        let screen_to_world = world_to_screen.inverse();
+/// `n` points spread evenly through `path`, keeping both ends.
+fn thin_polyline(path: &[DVec2], n: usize) -> Vec<DVec2> {
+    if path.len() <= n || n < 2 {
+        return path.to_vec();
+    }
+    (0..n)
+        .map(|i| path[i * (path.len() - 1) / (n - 1)])
+        .collect()
+}
 
-        if is_mouse_button_pressed(MouseButton::Left) {
-            // find nearest warp point to the cursor
-            let mouse_pos = dvec2(mouse_position().0.into(), mouse_position().1.into());
-            let mouse_pos = (screen_to_world * mouse_pos.extend(1.0)).xy();
+/// World-space positions along the path `sat` will follow over the next second, one per tick,
+/// stopping once it strays `max_distance` (per axis) from the black hole.
+fn predicted_path(
+    sat: Sat,
+    integrator: IntegratorKind,
+    gravity: Gravity,
+    max_distance: Option<f64>,
+) -> Vec<DVec2> {
+    let dot_dur = 1.0;
+    let end = sat.when + dot_dur;
+    let mut p = sat;
+    let bounds = max_distance.map(|d| Rect {
+        min: dvec2(-d, -d),
+        max: dvec2(d, d),
+    });
+    let mut path = vec![p.pos];
+    while p.when < end && bounds.is_none_or(|b| b.contains(p.pos)) {
+        p.step(TICK, integrator, gravity);
+        path.push(p.pos);
+    }
+    if let Some(bounds) = bounds {
+        path = clip_polyline(&path, bounds);
+    }
+    path
+}
 
-            let warp_pos = warp_points
-                .iter_mut()
-                .min_by_key(|p| (p.pos - mouse_pos).length_squared() as i64)
-                .unwrap();
+/// Move `sat` to `new_pos`, blending its velocity from unchanged (`assist` 0) to fully
+/// corrected (`assist` 1). The fully corrected velocity is rotated along with the position and
+/// rescaled to keep the same orbital energy, or, if that orbit would dip into the horizon, is the
+/// circular orbit velocity instead.
+fn assisted_warp(sat: &mut Sat, new_pos: DVec2, assist: f64, mu: f64) {
+    let raw = sat.vel;
+    let rotation = DVec2::from_angle(sat.pos.angle_between(new_pos));
+    let speed_sq =
+        raw.length_squared() + 2.0 * mu * (1.0 / new_pos.length() - 1.0 / sat.pos.length());
+    let mut corrected = Sat::new(
+        new_pos,
+        rotation.rotate(raw).normalize_or_zero() * speed_sq.max(0.0).sqrt(),
+        sat.when,
+    );
+    if corrected.periapsis(mu).length() <= EVENT_HORIZON_METERS {
+        let direction = if sat.pos.perp_dot(raw) < 0.0 {
+            -1.0
+        } else {
+            1.0
+        };
+        corrected.vel = new_pos.perp().normalize() * direction * (mu / new_pos.length()).sqrt();
+    }
+    sat.pos = new_pos;
+    sat.vel = raw.lerp(corrected.vel, assist.clamp(0.0, 1.0));
+}
 
-            dbg!(player.sat);
-            std::mem::swap(&mut player.sat.pos, &mut warp_pos.pos);
-            dbg!(player.sat);
-        }
+/// Whether the emergency warp, last used at `last_used`, has recharged by `now`.
+fn eject_ready(last_used: Option<f64>, now: f64) -> bool {
+    !last_used.is_some_and(|t| now - t < EJECT_COOLDOWN)
+}
 
-        player.sat.tick_to(time);
+/// Emergency warp: a circular orbit at the starting radius, picking up at `sat`'s time.
+fn eject(sat: &Sat, config: &Config, mu: f64) -> Result<Sat, String> {
+    Ok(Sat {
+        when: sat.when,
+        ..Sat::initial_orbit(config.start_radius, 1.0, mu)?
+    })
+}
 
-        clear_background(colors::BLACK);
+/// Drop a passive particle at `from`, thrown toward `to`, making room by forgetting the oldest.
+fn drop_test_particle(particles: &mut Vec<Sat>, from: DVec2, to: DVec2, when: f64) {
+    if particles.len() == MAX_TEST_PARTICLES {
+        particles.remove(0);
+    }
+    particles.push(Sat::new(from, (to - from) * DROP_SPEED_PER_METER, when));
+}
 
-        draw_circle(
-            screen_width() / 2.0,
-            screen_height() / 2.0,
-            20.0,
-            colors::VIOLET,
-        );
+/// Where `sat` would be right after warping to `warp_point`, leaving both as they are.
+fn preview_warp(sat: &Sat, warp_point: &WarpPoint, config: &Config) -> Sat {
+    let (mut sat, mut warp_point) = (*sat, warp_point.clone());
+    do_warp(&mut sat, &mut warp_point, config);
+    sat
+}
 
-        for wp in &warp_points {
-            let pos_screen = world_to_screen * wp.pos.extend(1.0);
-            draw_circle(pos_screen.x as f32, pos_screen.y as f32, 15.0, wp.color);
+/// Swap places with `warp_point`.
+fn do_warp(sat: &mut Sat, warp_point: &mut WarpPoint, config: &Config) {
+    let old_pos = sat.pos;
+    assisted_warp(sat, warp_point.pos, config.warp_assist, PULL);
+    sat.vel *= config.warp_damping;
+    warp_point.pos = old_pos;
+}
 
-            let dest_screen = world_to_screen * wp.win_destination.extend(1.0);
-            draw_rectangle(
-                dest_screen.x as f32 - 10.0,
-                dest_screen.y as f32 - 10.0,
-                20.0,
-                20.0,
-                wp.color,
-            );
+/// Step `initial` forward to `when`, returning every intermediate state including the first.
+#[cfg(test)]
+fn integrate_collect(initial: Sat, when: f64, dt: f64, integrator: IntegratorKind) -> Vec<Sat> {
+    let mut sat = initial;
+    let mut ret = vec![sat];
+    while sat.when < when {
+        sat.step(dt, integrator, Gravity::Point(PULL));
+        ret.push(sat);
+    }
+    ret
+}
 
-            // draw a thin line from the warp point to its destination
-            draw_line(
-                pos_screen.x as f32,
-                pos_screen.y as f32,
-                dest_screen.x as f32,
-                dest_screen.y as f32,
-                1.0,
-                wp.color,
-            );
-        }
+/// Neumaier's variant of Kahan summation. Keeps the rounding error lost by each addition so it
+/// can be added back, which matters when adding many tiny steps to a large total.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct CompensatedSum {
+    sum: f64,
+    compensation: f64,
+}
 
-        let points = 32;
-        let dot_dur = 1.0;
-        let mut p = player.sat;
-        p.vel = -p.vel;
-        let point_poses = (0..points).map(|i| {
-            let t = (i as f64).remap(0.0..((points - 1) as f64), 0.0..dot_dur);
-            p.tick_to(time + t);
-            let projected_pos_screen = world_to_screen * p.pos.extend(1.0);
-            vec2(projected_pos_screen.x as f32, projected_pos_screen.y as f32)
-        });
-        for (a, b) in point_poses.tuple_windows() {
-            draw_line(a.x, a.y, b.x, b.y, 2.0, YELLOW);
+impl CompensatedSum {
+    fn new(start: f64) -> Self {
+        Self {
+            sum: start,
+            compensation: 0.0,
         }
+    }
 
-        let player_pos_screen = world_to_screen * player.sat.pos.extend(1.0);
-        draw_circle(
-            player_pos_screen.x as f32,
-            player_pos_screen.y as f32,
-            15.0,
-            YELLOW,
-        );
+    fn add(&mut self, x: f64) {
+        let t = self.sum + x;
+        if self.sum.abs() >= x.abs() {
+            self.compensation += (self.sum - t) + x;
+        } else {
+            self.compensation += (x - t) + self.sum;
+        }
+        self.sum = t;
+    }
 
-        next_frame().await
+    fn total(&self) -> f64 {
+        self.sum + self.compensation
     }
 }
 
-trait Remap: Sized {
-    fn remap(self, current: Range<Self>, target: Range<Self>) -> Self;
+#[derive(Clone)]
+struct WarpPoint {
+    pos: DVec2,
+    color: Color,
+    win_destination: DVec2,
 }
 
-impl Remap for f64 {
-    fn remap(self, current: Range<Self>, target: Range<Self>) -> Self {
-        (self - current.start) / (current.end - current.start) * (target.end - target.start)
-            + target.start
+/// Size multiplier for a pulse that repeats every `period` seconds, bounded to `1 ± amplitude`.
+fn pulse_scale(time: f64, period: f64, amplitude: f64) -> f64 {
+    1.0 + amplitude * (std::f64::consts::TAU * time / period).sin()
+}
+
+/// How far through the step from `prev` to `cur` the frame at `time` falls, from 0 to 1.
+fn render_alpha(prev: &Sat, cur: &Sat, time: f64) -> f64 {
+    if cur.when > prev.when {
+        ((time - prev.when) / (cur.when - prev.when)).clamp(0.0, 1.0)
+    } else {
+        1.0
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Where to draw a sat `alpha` of the way through the step from `prev` to `cur`.
+fn interpolate_sat(prev: &Sat, cur: &Sat, alpha: f64) -> DVec2 {
+    prev.pos.lerp(cur.pos, alpha)
+}
 
-    #[test]
-    fn remap() {
-        assert_eq!(0.0.remap(0.0..1.0, 0.0..1.0), 0.0);
-        assert_eq!(1.0.remap(0.0..1.0, 0.0..1.0), 1.0);
-        assert_eq!(0.5.remap(0.0..1.0, 0.0..2.0), 1.0);
-        assert_eq!((-0.5).remap(0.0..-1.0, 0.0..2.0), 1.0);
+/// Uniformly distributed over the circle. Picking an angle rather than normalizing a random
+/// vector avoids a NaN when the vector happens to be near zero.
+fn random_point_on_circle(radius: f64) -> DVec2 {
+    DVec2::from_angle(gen_range(0.0, std::f64::consts::TAU)) * radius
+}
+
+/// The warp point nearest to `pos`, if any is within `capture_radius`.
+fn warp_point_near(
+    warp_points: &mut [WarpPoint],
+    pos: DVec2,
+    capture_radius: f64,
+) -> Option<&mut WarpPoint> {
+    warp_points
+        .iter_mut()
+        .filter(|p| p.pos.distance(pos) <= capture_radius)
+        .min_by(|a, b| a.pos.distance(pos).total_cmp(&b.pos.distance(pos)))
+}
+
+#[macroquad::main("ftl-hole")]
+async fn main() {
+    let mut config = Config::default();
+    // a missing theme file just means the default theme
+    if let Ok(text) = std::fs::read_to_string(THEME_PATH) {
+        match ThemeColors::parse(&text) {
+            Ok(theme) => config.theme = theme,
+            Err(e) => eprintln!("ignoring {THEME_PATH}: {e}"),
+        }
+    }
+
+    // usage: ftl-hole [--seed N] [--max-g G] [--warp-damping F] [--launch DEGREES,SPEED]
+    //                 [--start-radius M] [--start-speed F] [--aspect X] [--print-summary]
+    //                 [--no-gravity] [--replay clip.csv] [state-vector.csv]
+    let mut state_path = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--replay" {
+            let Some(path) = args.next() else {
+                eprintln!("--replay needs a clip file");
+                std::process::exit(1);
+            };
+            let clip = std::fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|text| OrbitClip::from_csv(&text));
+            match clip {
+                Ok(clip) => replay(&clip, &config.theme).await,
+                Err(e) => {
+                    eprintln!("can't replay {path}: {e}");
+                    std::process::exit(1);
+                }
+            }
+            return;
+        } else if arg == "--launch" {
+            let launch = args.next().and_then(|s| {
+                let (angle, speed) = s.split_once(',')?;
+                Some(Launch {
+                    angle: angle.trim().parse::<f64>().ok()?.to_radians(),
+                    speed: speed.trim().parse().ok()?,
+                })
+            });
+            match launch {
+                Some(launch) => config.launch = Some(launch),
+                None => {
+                    eprintln!("--launch needs an angle in degrees and a speed, like 90,1.5");
+                    std::process::exit(1);
+                }
+            }
+        } else if arg == "--max-g" {
+            match args.next().and_then(|s| s.parse::<f64>().ok()) {
+                Some(g) if g > 0.0 && g.is_finite() => config.max_g = Some(g),
+                _ => {
+                    eprintln!("--max-g needs a positive number");
+                    std::process::exit(1);
+                }
+            }
+        } else if arg == "--warp-damping" {
+            match args.next().and_then(|s| s.parse::<f64>().ok()) {
+                Some(f) if f > 0.0 && f <= 1.0 => config.warp_damping = f,
+                _ => {
+                    eprintln!("--warp-damping needs a number above 0 and at most 1");
+                    std::process::exit(1);
+                }
+            }
+        } else if arg == "--start-radius" {
+            match args.next().and_then(|s| s.parse::<f64>().ok()) {
+                Some(r) if r > 0.0 && r.is_finite() => config.start_radius = r,
+                _ => {
+                    eprintln!("--start-radius needs a positive number of meters");
+                    std::process::exit(1);
+                }
+            }
+        } else if arg == "--start-speed" {
+            match args.next().and_then(|s| s.parse::<f64>().ok()) {
+                Some(f) if f >= 0.0 && f.is_finite() => config.start_speed = f,
+                _ => {
+                    eprintln!("--start-speed needs a fraction of circular speed");
+                    std::process::exit(1);
+                }
+            }
+        } else if arg == "--aspect" {
+            match args.next().and_then(|s| s.parse::<f64>().ok()) {
+                Some(x) if x > 0.0 && x.is_finite() => config.world_aspect = x,
+                _ => {
+                    eprintln!("--aspect needs a positive number");
+                    std::process::exit(1);
+                }
+            }
+        } else if arg == "--print-summary" {
+            config.print_run_summary = true;
+        } else if arg == "--no-gravity" {
+            config.no_gravity = true;
+        } else if arg == "--seed" {
+            match args.next().and_then(|s| s.parse().ok()) {
+                Some(seed) => config.seed = Some(seed),
+                None => {
+                    eprintln!("--seed needs a number");
+                    std::process::exit(1);
+                }
+            }
+        } else {
+            state_path = Some(arg);
+        }
+    }
+
+    let today = daily::civil_date(miniquad::date::now());
+    let seed = config
+        .seed
+        .unwrap_or_else(|| daily::daily_seed(today.0, today.1, today.2));
+    srand(seed);
+    let seed_label = match config.seed {
+        Some(_) => format!("seed {seed}"),
+        None => format!(
+            "daily challenge {}-{:02}-{:02} (seed {seed})",
+            today.0, today.1, today.2
+        ),
+    };
+
+    // create 4 warp points with random positions and destinations
+    let mut warp_points = config.theme.warp_palette.map(|color| WarpPoint {
+        color,
+        pos: random_point_on_circle(WORLD_RADIUS_METERS),
+        win_destination: random_point_on_circle(WORLD_RADIUS_METERS),
+    });
+
+    let default_orbit = match config.launch {
+        Some(launch) => launch.orbit(PULL),
+        None => Sat::initial_orbit(config.start_radius, config.start_speed, PULL),
+    };
+    let default_orbit = match default_orbit {
+        Ok(sat) => sat,
+        Err(e) => {
+            eprintln!("invalid config: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    // optionally start from a real orbit, given as a csv state vector; reset returns here too
+    let start_orbit = match state_path {
+        Some(path) => {
+            let imported = std::fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|text| {
+                    state_import::import_state(
+                        &text,
+                        PULL,
+                        WORLD_RADIUS_METERS / 2.0,
+                        EVENT_HORIZON_METERS,
+                    )
+                });
+            match imported {
+                Ok(sat) => sat,
+                Err(e) => {
+                    eprintln!("can't import {path}: {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        None => default_orbit,
+    };
+    let mut player = Player { sat: start_orbit };
+    // the player's state when their orbit last changed, for comparing against the exact orbit
+    let mut shadow_epoch = player.sat;
+    let mut focused: Option<usize> = None;
+    let mut orbits = OrbitCounter::default();
+    let mut trail = Trail::new(2048, 1.0 / 60.0);
+    let mut comet_tail = ParticleSystem::new(256, 1.0);
+    let mut test_particles: Vec<Sat> = Vec::new();
+    // where a test particle drop started, in world coordinates
+    let mut drag_start: Option<DVec2> = None;
+    let mut recording: Option<OrbitClip> = None;
+    let mut pinned: Vec<PinnedOrbit> = Vec::new();
+    let mut g_force = GForceMonitor::new(config.max_g);
+    let mut orbit_stats = OrbitStats::default();
+    let mut speed_history = SpeedHistory::new(10.0);
+    let mut warps = 0;
+    let mut run_start = 0.0;
+    // why the run ended, if it has
+    let mut lost: Option<&str> = None;
+    let mut substeps: Vec<DVec2> = Vec::new();
+    // drives cosmetic animations, which may run slower than the simulation
+    let mut display_time = 0.0;
+    let mut clock = SimClock::default();
+    let mut photo: Option<PhotoMode> = None;
+    let mut last_eject: Option<f64> = None;
+
+    loop {
+        let mut time = clock.now(get_time());
+
+        if is_key_pressed(KeyCode::Tab) {
+            focused = cycle_focus(focused, warp_points.len());
+        }
+        if is_key_pressed(KeyCode::Escape) {
+            focused = None;
+        }
+
+        let view_center = focused.map_or(DVec2::ZERO, |i| warp_points[i].pos);
+        if is_key_pressed(KeyCode::F) {
+            photo = match photo {
+                Some(p) => {
+                    if p.resume_on_exit {
+                        clock.resume(get_time());
+                    }
+                    None
+                }
+                None => {
+                    let p = PhotoMode::enter(view_center, clock.paused());
+                    clock.pause(get_time());
+                    // a half-finished particle drop would land in the frozen scene
+                    drag_start = None;
+                    Some(p)
+                }
+            };
+        }
+        if let Some(p) = &mut photo {
+            let dt = get_frame_time() as f64;
+            let pan_speed = WORLD_RADIUS_METERS / p.zoom;
+            for (key, dir) in [
+                (KeyCode::Left, dvec2(-1.0, 0.0)),
+                (KeyCode::Right, dvec2(1.0, 0.0)),
+                (KeyCode::Up, dvec2(0.0, -1.0)),
+                (KeyCode::Down, dvec2(0.0, 1.0)),
+            ] {
+                if is_key_down(key) {
+                    p.pan(dir * pan_speed * dt);
+                }
+            }
+            if is_key_down(KeyCode::Equal) {
+                p.zoom_by(2f64.powf(dt));
+            }
+            if is_key_down(KeyCode::Minus) {
+                p.zoom_by(0.5f64.powf(dt));
+            }
+            if is_key_pressed(KeyCode::H) {
+                p.hide_overlays = !p.hide_overlays;
+            }
+        }
+        let screen = dvec2(screen_width().into(), screen_height().into());
+        let (center, zoom) = photo.map_or((view_center, 1.0), |p| (p.center, p.zoom));
+        let world_to_screen =
+            build_view(screen, config.world_aspect, center, config.log_radial_scale)
+                .zoomed(screen, zoom);
+        let show_overlays = !photo.is_some_and(|p| p.hide_overlays);
+
+        if is_key_pressed(KeyCode::C) {
+            config.show_capture_radius = !config.show_capture_radius;
+        }
+        if is_key_pressed(KeyCode::I) {
+            config.integrator = config.integrator.next();
+        }
+        if is_key_pressed(KeyCode::R) && photo.is_none() {
+            // put the player back on the starting orbit, leaving the warp points where they are
+            player.sat = Sat {
+                when: time,
+                ..start_orbit
+            };
+            shadow_epoch = player.sat;
+            g_force = GForceMonitor::new(config.max_g);
+            orbit_stats = OrbitStats::default();
+            speed_history = SpeedHistory::new(10.0);
+            orbits = OrbitCounter::default();
+            trail.clear();
+            warps = 0;
+            last_eject = None;
+            run_start = time;
+            lost = None;
+        }
+        if is_key_pressed(KeyCode::H) && photo.is_none() {
+            config.show_hud = !config.show_hud;
+        }
+        if is_key_pressed(KeyCode::A) {
+            config.show_apsis_line = !config.show_apsis_line;
+        }
+        if is_key_pressed(KeyCode::S) {
+            config.show_schwarzschild_radius = !config.show_schwarzschild_radius;
+        }
+        if is_key_pressed(KeyCode::T) {
+            config.time_dilation_effect = !config.time_dilation_effect;
+        }
+        if is_key_pressed(KeyCode::O) {
+            config.show_trail = !config.show_trail;
+        }
+        if is_key_pressed(KeyCode::D) {
+            config.show_substeps = !config.show_substeps;
+        }
+        if is_key_pressed(KeyCode::LeftBracket) {
+            config.warp_assist = (config.warp_assist - 0.25).max(0.0);
+        }
+        if is_key_pressed(KeyCode::RightBracket) {
+            config.warp_assist = (config.warp_assist + 0.25).min(1.0);
+        }
+        if is_key_pressed(KeyCode::L) {
+            config.log_radial_scale = match config.log_radial_scale {
+                Some(_) => None,
+                None => Some(EVENT_HORIZON_METERS),
+            };
+        }
+        if is_key_pressed(KeyCode::B) {
+            config.sandbox = !config.sandbox;
+            test_particles.clear();
+            drag_start = None;
+        }
+        if is_key_pressed(KeyCode::V) {
+            recording = match recording.take() {
+                Some(clip) => {
+                    save_clip(&clip);
+                    None
+                }
+                None => Some(OrbitClip::default()),
+            };
+        }
+        if is_key_pressed(KeyCode::M) {
+            pin_orbit(&mut pinned, player.sat, PULL, &config.theme.orbit_palette);
+        }
+        if is_key_pressed(KeyCode::Backspace) {
+            pinned.clear();
+        }
+        if is_key_pressed(KeyCode::Q) {
+            config.show_legend = !config.show_legend;
+        }
+        if is_key_pressed(KeyCode::Z) {
+            config.show_position_error = !config.show_position_error;
+        }
+        if is_key_pressed(KeyCode::K) {
+            config.show_comet_tail = !config.show_comet_tail;
+        }
+        if is_key_pressed(KeyCode::G) {
+            config.show_speed_graph = !config.show_speed_graph;
+        }
+        if is_key_pressed(KeyCode::E) {
+            config.show_orbit_vectors = !config.show_orbit_vectors;
+        }
+        if is_key_pressed(KeyCode::X)
+            && photo.is_none()
+            && lost.is_none()
+            && eject_ready(last_eject, time)
+        {
+            match eject(&player.sat, &config, PULL) {
+                Ok(sat) => {
+                    player.sat = sat;
+                    shadow_epoch = sat;
+                    orbits.teleported();
+                    last_eject = Some(time);
+                }
+                Err(e) => eprintln!("can't eject: {e}"),
+            }
+        }
+        if is_key_pressed(KeyCode::P) && photo.is_none() {
+            if clock.paused() {
+                clock.resume(get_time());
+            } else {
+                clock.pause(get_time());
+            }
+        }
+        // while paused, N advances exactly one tick
+        let single_step = clock.paused() && photo.is_none() && is_key_pressed(KeyCode::N);
+        if single_step {
+            time = time.max(player.sat.when) + TICK;
+            clock.advance_paused(time);
+        }
+
+        if is_mouse_button_pressed(MouseButton::Left) && photo.is_none() {
+            // find nearest warp point to the cursor
+            let mouse_pos = dvec2(mouse_position().0.into(), mouse_position().1.into());
+            let mouse_pos = world_to_screen.unproject(mouse_pos);
+            let radius = config.selection_radius();
+            if let Some(warp_pos) = warp_point_near(&mut warp_points, mouse_pos, radius) {
+                dbg!(player.sat);
+                do_warp(&mut player.sat, warp_pos, &config);
+                shadow_epoch = player.sat;
+                orbits.teleported();
+                warps += 1;
+                dbg!(player.sat);
+            }
+        }
+
+        if config.sandbox && photo.is_none() {
+            let mouse_pos = dvec2(mouse_position().0.into(), mouse_position().1.into());
+            let mouse_pos = world_to_screen.unproject(mouse_pos);
+            if is_mouse_button_pressed(MouseButton::Right) {
+                drag_start = Some(mouse_pos);
+            }
+            if is_mouse_button_released(MouseButton::Right) {
+                if let Some(from) = drag_start.take() {
+                    drop_test_particle(&mut test_particles, from, mouse_pos, time);
+                }
+            }
+        }
+
+        if !clock.paused() || single_step {
+            for particle in &mut test_particles {
+                particle.tick_to(time, config.integrator, config.gravity());
+            }
+            test_particles.retain(|p| p.pos.length() > EVENT_HORIZON_METERS);
+        }
+
+        // the last step usually overshoots the frame, so draw the player partway through it
+        let mut render_prev = player.sat;
+
+        // a lost ship stays where it was lost until reset
+        let advancing = lost.is_none() && (!clock.paused() || single_step);
+        if advancing {
+            let crossing = match config.gravity() {
+                Gravity::Point(mu) => horizon::crossing_time(&player.sat, mu, EVENT_HORIZON_METERS),
+                // the analytic crossing assumes a conic, so check the stepped path instead
+                Gravity::Off => Crossing::Unsolvable,
+            };
+            let elapsed = time - player.sat.when;
+            substeps.clear();
+            substeps.push(player.sat.pos);
+            let mut last = player.sat;
+            player
+                .sat
+                .tick_to_with(time, config.integrator, config.gravity(), |s| {
+                    substeps.push(s.pos);
+                    // the peak is over in a few substeps at periapsis, so don't wait for the frame end
+                    g_force.sample(s.acceleration(config.gravity()));
+                    // a long frame can cover much of an orbit, so follow it substep by substep
+                    orbits.observe(s.pos);
+                    trail.push(s.pos, orbits.completed, s.when);
+                    render_prev = last;
+                    last = *s;
+                });
+            if single_step {
+                println!("{:?}", player.sat);
+            }
+            for p in &substeps {
+                orbit_stats.sample(*p);
+            }
+            speed_history.push(player.sat.when, player.sat.vel.length());
+            if let Some(clip) = &mut recording {
+                clip.push(player.sat);
+                if clip.duration() >= CLIP_SECONDS {
+                    save_clip(clip);
+                    recording = None;
+                }
+            }
+
+            let fell_in = match crossing {
+                Crossing::After(t) => t <= elapsed,
+                Crossing::Never => false,
+                // fall back to checking the path we actually stepped along
+                Crossing::Unsolvable => substeps.iter().any(|p| p.length() <= EVENT_HORIZON_METERS),
+            };
+            if fell_in {
+                lost = Some("fell into the black hole");
+            } else if g_force.exceeded() {
+                lost = Some("structural failure");
+            }
+
+            if let Some(reason) = lost {
+                let summary = RunSummary::from_monitors(
+                    reason,
+                    player.sat.when - run_start,
+                    warps,
+                    &g_force,
+                    &orbit_stats,
+                    EVENT_HORIZON_METERS,
+                );
+                let json = summary.to_json();
+                if let Err(e) = std::fs::write(SUMMARY_PATH, &json) {
+                    eprintln!("can't write {SUMMARY_PATH}: {e}");
+                }
+                if config.print_run_summary {
+                    print!("{json}");
+                }
+            }
+        }
+
+        let display_rate = if config.time_dilation_effect {
+            dilation_factor(
+                player.sat.pos.length() - EVENT_HORIZON_METERS,
+                EVENT_HORIZON_METERS,
+            )
+        } else {
+            1.0
+        };
+        display_time += get_frame_time() as f64 * display_rate;
+        if config.show_comet_tail && advancing {
+            // cosmetic, so it runs on the dilated display clock like the other animations
+            comet_tail.step(get_frame_time() as f64 * display_rate);
+            // slower than the player, so they fall behind it
+            comet_tail.spawn(player.sat.pos, player.sat.vel * 0.5);
+        }
+
+        clear_background(config.theme.background);
+
+        draw_world_disc(
+            DVec2::ZERO,
+            EVENT_HORIZON_METERS,
+            &world_to_screen,
+            config.theme.black_hole,
+        );
+
+        if config.show_schwarzschild_radius && show_overlays {
+            let origin_screen = world_to_screen.project(DVec2::ZERO);
+            let r = world_to_screen
+                .project(dvec2(0.0, schwarzschild_radius(BLACK_HOLE_MASS)))
+                .distance(origin_screen);
+            // far below a pixel at this mass, so keep it visible as a speck
+            draw_circle_lines(
+                origin_screen.x as f32,
+                origin_screen.y as f32,
+                (r as f32).max(1.0),
+                1.0,
+                config.theme.schwarzschild_radius,
+            );
+        }
+
+        // warp points are stationary, so they all pulse with the same fixed period
+        let pulse = pulse_scale(display_time, 2.0, 0.1);
+        for wp in &warp_points {
+            let pos_screen = world_to_screen.project(wp.pos);
+            draw_circle(
+                pos_screen.x as f32,
+                pos_screen.y as f32,
+                15.0 * pulse as f32,
+                wp.color,
+            );
+
+            // there's no ring to draw when anywhere selects
+            if show_overlays && config.show_capture_radius && config.selection_radius().is_finite()
+            {
+                let ring =
+                    world_circle_outline(wp.pos, config.selection_radius(), &world_to_screen);
+                for (a, b) in ring.iter().tuple_windows() {
+                    draw_line(
+                        a.x as f32,
+                        a.y as f32,
+                        b.x as f32,
+                        b.y as f32,
+                        1.0,
+                        Color { a: 0.3, ..wp.color },
+                    );
+                }
+            }
+
+            let dest_screen = world_to_screen.project(wp.win_destination);
+            draw_rectangle(
+                dest_screen.x as f32 - 10.0,
+                dest_screen.y as f32 - 10.0,
+                20.0,
+                20.0,
+                wp.color,
+            );
+
+            // draw a thin line from the warp point to its destination
+            draw_line(
+                pos_screen.x as f32,
+                pos_screen.y as f32,
+                dest_screen.x as f32,
+                dest_screen.y as f32,
+                1.0,
+                wp.color,
+            );
+        }
+
+        if config.show_trail && show_overlays {
+            for ((a, _), (b, orbit)) in trail.points.iter().tuple_windows() {
+                let a = world_to_screen.project(*a);
+                let b = world_to_screen.project(*b);
+                draw_line(
+                    a.x as f32,
+                    a.y as f32,
+                    b.x as f32,
+                    b.y as f32,
+                    1.0,
+                    config.theme.orbit_color(*orbit),
+                );
+            }
+        }
+
+        if config.show_comet_tail {
+            for (particle, life) in comet_tail.alive() {
+                let p = world_to_screen.project(particle.pos);
+                let color = Color {
+                    a: config.theme.comet_tail.a * life as f32,
+                    ..config.theme.comet_tail
+                };
+                draw_circle(p.x as f32, p.y as f32, 2.0, color);
+            }
+        }
+
+        if show_overlays {
+            for pin in &pinned {
+                let color = Color {
+                    a: 0.35,
+                    ..pin.color
+                };
+                for (a, b) in pin.path.iter().tuple_windows() {
+                    let a = world_to_screen.project(*a);
+                    let b = world_to_screen.project(*b);
+                    draw_line(a.x as f32, a.y as f32, b.x as f32, b.y as f32, 1.0, color);
+                }
+            }
+        }
+
+        for particle in &test_particles {
+            let p = world_to_screen.project(particle.pos);
+            draw_circle(p.x as f32, p.y as f32, 3.0, config.theme.test_particle);
+        }
+        if let Some(from) = drag_start {
+            let a = world_to_screen.project(from);
+            draw_line(
+                a.x as f32,
+                a.y as f32,
+                mouse_position().0,
+                mouse_position().1,
+                1.0,
+                config.theme.test_particle,
+            );
+        }
+
+        // photo mode shows the prediction in full
+        let prediction_limit = match photo {
+            Some(_) => None,
+            None => config.max_prediction_distance,
+        };
+        let samples = predicted_path_for_view(
+            player.sat,
+            &world_to_screen,
+            config.integrator,
+            config.gravity(),
+            prediction_limit,
+        );
+        let point_poses = samples.iter().map(|p| {
+            let projected_pos_screen = world_to_screen.project(*p);
+            vec2(projected_pos_screen.x as f32, projected_pos_screen.y as f32)
+        });
+        for (a, b) in point_poses.tuple_windows() {
+            draw_line(a.x, a.y, b.x, b.y, 2.0, config.theme.prediction);
+        }
+
+        // preview the orbit we'd be on after warping to the focused point
+        if let Some(wp) = focused.map(|i| &warp_points[i]).filter(|_| show_overlays) {
+            let preview = preview_warp(&player.sat, wp, &config);
+            let samples = predicted_path_for_view(
+                preview,
+                &world_to_screen,
+                config.integrator,
+                config.gravity(),
+                prediction_limit,
+            );
+            for (a, b) in samples.iter().tuple_windows() {
+                let a = world_to_screen.project(*a);
+                let b = world_to_screen.project(*b);
+                draw_line(
+                    a.x as f32,
+                    a.y as f32,
+                    b.x as f32,
+                    b.y as f32,
+                    1.0,
+                    Color {
+                        a: 0.5,
+                        ..config.theme.prediction
+                    },
+                );
+            }
+        }
+
+        if config.show_apsis_line && show_overlays {
+            let (peri, apo) = apsis_line(&player.sat, PULL);
+            // for unbound orbits, draw a ray from the black hole through periapsis instead
+            let far = apo.unwrap_or(peri.normalize() * WORLD_RADIUS_METERS * 1.5);
+            let near_screen = world_to_screen.project(peri);
+            let far_screen = world_to_screen.project(far);
+            let origin_screen = world_to_screen.project(DVec2::ZERO);
+            let from = if apo.is_some() {
+                near_screen
+            } else {
+                origin_screen
+            };
+            draw_line(
+                from.x as f32,
+                from.y as f32,
+                far_screen.x as f32,
+                far_screen.y as f32,
+                1.0,
+                config.theme.apsis_line,
+            );
+            draw_circle(
+                near_screen.x as f32,
+                near_screen.y as f32,
+                4.0,
+                config.theme.apsis_line,
+            );
+        }
+
+        let alpha = render_alpha(&render_prev, &player.sat, time);
+        let player_pos_screen =
+            world_to_screen.project(interpolate_sat(&render_prev, &player.sat, alpha));
+        draw_circle(
+            player_pos_screen.x as f32,
+            player_pos_screen.y as f32,
+            15.0,
+            config.theme.player,
+        );
+
+        // the exact solution is a conic, so there's nothing to compare against without gravity
+        let shadow = match config.gravity() {
+            Gravity::Point(mu) if config.show_position_error => {
+                shadow::analytic_position(&shadow_epoch, mu, player.sat.when)
+            }
+            _ => None,
+        };
+        if let Some(shadow) = shadow.filter(|_| show_overlays) {
+            let p = world_to_screen.project(shadow);
+            draw_circle_lines(p.x as f32, p.y as f32, 8.0, 2.0, config.theme.shadow);
+        }
+
+        if config.show_substeps && show_overlays {
+            let screen = substeps
+                .iter()
+                .map(|p| world_to_screen.project(*p))
+                .collect_vec();
+            for (a, b) in screen.iter().tuple_windows() {
+                draw_line(
+                    a.x as f32,
+                    a.y as f32,
+                    b.x as f32,
+                    b.y as f32,
+                    2.0,
+                    config.theme.substeps,
+                );
+            }
+            if let (Some(a), Some(b)) = (screen.first(), screen.last()) {
+                draw_line(
+                    a.x as f32,
+                    a.y as f32,
+                    b.x as f32,
+                    b.y as f32,
+                    1.0,
+                    config.theme.chord,
+                );
+            }
+        }
+
+        if config.show_orbit_vectors && show_overlays {
+            let e = eccentricity_vector(player.sat.pos, player.sat.vel, PULL);
+            let e_tip = player.sat.pos + e * WORLD_RADIUS_METERS / 4.0;
+            let e_tip_screen = world_to_screen.project(e_tip);
+            draw_line(
+                player_pos_screen.x as f32,
+                player_pos_screen.y as f32,
+                e_tip_screen.x as f32,
+                e_tip_screen.y as f32,
+                2.0,
+                config.theme.eccentricity,
+            );
+
+            // angular momentum is out of the plane in 2d, so only its sign is shown: a filled
+            // dot for counterclockwise (in world space) and a ring for clockwise
+            let h = player.sat.pos.perp_dot(player.sat.vel);
+            if h > 0.0 {
+                draw_circle(
+                    player_pos_screen.x as f32,
+                    player_pos_screen.y as f32,
+                    4.0,
+                    config.theme.angular_momentum,
+                );
+            } else {
+                draw_circle_lines(
+                    player_pos_screen.x as f32,
+                    player_pos_screen.y as f32,
+                    6.0,
+                    2.0,
+                    config.theme.angular_momentum,
+                );
+            }
+        }
+
+        if config.show_hud && show_overlays {
+            let mut lines = vec![
+                seed_label.clone(),
+                format!("orbit {}", orbits.completed + 1),
+                format!("e {:.3}", player.sat.eccentricity(PULL)),
+                format!("a {:.0} m", player.sat.semi_major_axis(PULL)),
+                format!(
+                    "flight path angle {:.1}°",
+                    player.sat.flight_path_angle().to_degrees()
+                ),
+                format_energy(
+                    player.sat.specific_energy(PULL),
+                    player.sat.pos.length(),
+                    PULL,
+                ),
+                format!("peak {:.0} g", g_force.peak()),
+                format!("warp assist {:.0}%", config.warp_assist * 100.0),
+                format!("warp damping x{:.2}", config.warp_damping),
+                match last_eject {
+                    Some(t) if !eject_ready(last_eject, time) => {
+                        format!("eject in {:.0} s", EJECT_COOLDOWN - (time - t))
+                    }
+                    _ => "eject ready (X)".to_string(),
+                },
+            ];
+            if let Some(i) = focused {
+                let wp = &warp_points[i];
+                lines.push(format!(
+                    "warp point {}: {:.0} m from destination, {:.0} m from you",
+                    i + 1,
+                    wp.pos.distance(wp.win_destination),
+                    wp.pos.distance(player.sat.pos),
+                ));
+            }
+            if config.show_schwarzschild_radius {
+                lines.push(format!(
+                    "schwarzschild radius {:.2e} m",
+                    schwarzschild_radius(BLACK_HOLE_MASS)
+                ));
+            }
+            if config.show_position_error {
+                lines.push(match shadow {
+                    Some(shadow) => {
+                        format!("integrator error {:.2e} m", shadow.distance(player.sat.pos))
+                    }
+                    None => "integrator error unavailable on an open orbit".to_string(),
+                });
+            }
+            if let Some(clip) = &recording {
+                lines.push(format!(
+                    "recording clip {:.1} s, V to stop",
+                    clip.duration()
+                ));
+            }
+            if photo.is_some() {
+                lines.push("photo mode: arrows pan, +/- zoom, H hides overlays".to_string());
+            } else if clock.paused() {
+                lines.push(format!("paused at t = {:.3} s, N to step", player.sat.when));
+            }
+            if config.world_aspect != 1.0 {
+                lines.push(format!(
+                    "view stretched {:.2}:1, circles appear as ellipses",
+                    config.world_aspect
+                ));
+            }
+            for (i, line) in lines.iter().enumerate() {
+                draw_text(line, 10.0, 20.0 + 20.0 * i as f32, 20.0, config.theme.hud);
+            }
+        }
+
+        if config.show_speed_graph && show_overlays {
+            draw_speed_graph(&speed_history, &config);
+        }
+
+        if config.show_legend && show_overlays {
+            draw_legend(&legend(&config, !pinned.is_empty(), focused.is_some()));
+        }
+
+        if let Some(reason) = lost {
+            draw_text(
+                &format!("{reason}, press R to reset"),
+                screen_width() / 2.0 - 180.0,
+                screen_height() / 2.0 - 60.0,
+                30.0,
+                config.theme.danger,
+            );
+        }
+
+        next_frame().await
+    }
+}
+
+fn save_clip(clip: &OrbitClip) {
+    match std::fs::write(CLIP_PATH, clip.to_csv()) {
+        Ok(()) => println!("saved {:.1} s clip to {CLIP_PATH}", clip.duration()),
+        Err(e) => eprintln!("can't write {CLIP_PATH}: {e}"),
+    }
+}
+
+/// Play back a recorded orbit on a loop, with nothing else of the game running.
+async fn replay(clip: &OrbitClip, theme: &ThemeColors) {
+    let start = clip.samples[0].when;
+    loop {
+        let view = build_view(
+            dvec2(screen_width().into(), screen_height().into()),
+            1.0,
+            DVec2::ZERO,
+            None,
+        );
+        clear_background(theme.background);
+
+        draw_world_disc(DVec2::ZERO, EVENT_HORIZON_METERS, &view, theme.black_hole);
+        for (a, b) in clip.samples.iter().tuple_windows() {
+            let a = view.project(a.pos);
+            let b = view.project(b.pos);
+            draw_line(
+                a.x as f32,
+                a.y as f32,
+                b.x as f32,
+                b.y as f32,
+                1.0,
+                theme.prediction,
+            );
+        }
+        let t = start + get_time().rem_euclid(clip.duration().max(f64::EPSILON));
+        if let Some(sat) = clip.sample_at(t) {
+            let p = view.project(sat.pos);
+            draw_circle(p.x as f32, p.y as f32, 15.0, theme.player);
+        }
+
+        next_frame().await
+    }
+}
+
+/// What each color drawn with the current overlays means, in drawing order.
+fn legend(config: &Config, any_pinned: bool, warp_focused: bool) -> Vec<(&'static str, Color)> {
+    let theme = &config.theme;
+    let overlays = [
+        (true, "player", theme.player),
+        (true, "predicted path", theme.prediction),
+        (
+            warp_focused,
+            "path after warping",
+            Color {
+                a: 0.5,
+                ..theme.prediction
+            },
+        ),
+        (
+            config.show_capture_radius && config.selection_radius().is_finite(),
+            "warp capture radius",
+            Color {
+                a: 0.3,
+                ..theme.warp_palette[0]
+            },
+        ),
+        (
+            config.show_trail,
+            "trail, colored by orbit",
+            theme.orbit_color(0),
+        ),
+        (any_pinned, "pinned orbits", theme.orbit_color(0)),
+        (config.show_comet_tail, "comet tail", theme.comet_tail),
+        (config.sandbox, "test particles", theme.test_particle),
+        (config.show_apsis_line, "line of apsides", theme.apsis_line),
+        (
+            config.show_schwarzschild_radius,
+            "schwarzschild radius",
+            theme.schwarzschild_radius,
+        ),
+        (config.show_position_error, "exact position", theme.shadow),
+        (config.show_substeps, "integrator substeps", theme.substeps),
+        (config.show_substeps, "frame chord", theme.chord),
+        (
+            config.show_orbit_vectors,
+            "eccentricity",
+            theme.eccentricity,
+        ),
+        (
+            config.show_orbit_vectors,
+            "angular momentum",
+            theme.angular_momentum,
+        ),
+        (config.show_speed_graph, "speed", theme.speed_graph),
+    ];
+    overlays
+        .into_iter()
+        .filter(|(active, _, _)| *active)
+        .map(|(_, label, color)| (label, color))
+        .collect()
+}
+
+/// A swatch and label per entry in the top right corner.
+fn draw_legend(entries: &[(&str, Color)]) {
+    let x = screen_width() - 220.0;
+    for (i, (label, color)) in entries.iter().enumerate() {
+        let y = 20.0 + 20.0 * i as f32;
+        draw_rectangle(x, y - 12.0, 12.0, 12.0, *color);
+        draw_text(label, x + 20.0, y, 20.0, *color);
+    }
+}
+
+/// Speed over time in the bottom left corner, scaled to fill the graph vertically.
+fn draw_speed_graph(history: &SpeedHistory, config: &Config) {
+    let (Some(min), Some(max), Some(current)) = (history.min(), history.max(), history.current())
+    else {
+        return;
+    };
+    let Some(&(start, _)) = history.samples.front() else {
+        return;
+    };
+    let Some(&(end, _)) = history.samples.back() else {
+        return;
+    };
+    let (width, height) = (240.0, 80.0);
+    let origin = dvec2(10.0, screen_height() as f64 - 10.0);
+    let to_graph = |(t, speed): (f64, f64)| {
+        let x = if end > start {
+            t.remap(start..end, 0.0..width)
+        } else {
+            width
+        };
+        let y = if max > min {
+            speed.remap(min..max, 0.0..height)
+        } else {
+            height / 2.0
+        };
+        origin + dvec2(x, -y)
+    };
+    for (a, b) in history.samples.iter().map(|s| to_graph(*s)).tuple_windows() {
+        draw_line(
+            a.x as f32,
+            a.y as f32,
+            b.x as f32,
+            b.y as f32,
+            1.0,
+            config.theme.speed_graph,
+        );
+    }
+    draw_text(
+        &format!("speed {current:.0} m/s ({min:.0} to {max:.0})"),
+        origin.x as f32,
+        (origin.y - height - 6.0) as f32,
+        18.0,
+        config.theme.hud,
+    );
+}
+
+trait Remap: Sized {
+    fn remap(self, current: Range<Self>, target: Range<Self>) -> Self;
+}
+
+impl Remap for f64 {
+    fn remap(self, current: Range<Self>, target: Range<Self>) -> Self {
+        (self - current.start) / (current.end - current.start) * (target.end - target.start)
+            + target.start
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The default starting orbit.
+    fn starting_orbit() -> Sat {
+        let config = Config::default();
+        Sat::initial_orbit(config.start_radius, config.start_speed, PULL).unwrap()
+    }
+
+    #[test]
+    fn remap() {
+        assert_eq!(0.0.remap(0.0..1.0, 0.0..1.0), 0.0);
+        assert_eq!(1.0.remap(0.0..1.0, 0.0..1.0), 1.0);
+        assert_eq!(0.5.remap(0.0..1.0, 0.0..2.0), 1.0);
+        assert_eq!((-0.5).remap(0.0..-1.0, 0.0..2.0), 1.0);
+    }
+
+    #[test]
+    fn capture_ring_matches_selection() {
+        let config = Config::default();
+        let center = dvec2(100.0, 0.0);
+        let mut warp_points = [WarpPoint {
+            pos: center,
+            color: ThemeColors::default().warp_palette[0],
+            win_destination: DVec2::ZERO,
+        }];
+
+        for log_radial in [None, Some(EVENT_HORIZON_METERS)] {
+            let view = build_view(dvec2(800.0, 600.0), 1.5, dvec2(50.0, 20.0), log_radial);
+
+            // every point of the drawn ring, mapped back to world space, is on the selection radius
+            let radius = config.selection_radius();
+            for p in world_circle_outline(center, radius, &view) {
+                let offset = view.unproject(p) - center;
+                assert!((offset.length() - radius).abs() < 1e-9);
+
+                let edge = center + offset * 0.999;
+                assert!(warp_point_near(&mut warp_points, edge, radius).is_some());
+                let outside = center + offset * 1.001;
+                assert!(warp_point_near(&mut warp_points, outside, radius).is_none());
+            }
+        }
+
+        // full assist selects from anywhere, so no finite ring can match it
+        let assisted = Config {
+            warp_assist: 1.0,
+            ..Config::default()
+        };
+        assert!(!assisted.selection_radius().is_finite());
+    }
+
+    #[test]
+    fn anisotropic_view_round_trips() {
+        let view = build_view(dvec2(1280.0, 720.0), 2.0, DVec2::ZERO, None);
+        let p = dvec2(300.0, -700.0);
+        let screen = view.project(p);
+        assert!((view.unproject(screen) - p).length() < 1e-9);
+
+        // x is stretched twice as much as y
+        let unit = view.affine.transform_vector2(dvec2(1.0, 1.0));
+        assert!((unit.x - 2.0 * unit.y).abs() < 1e-9);
+    }
+
+    #[test]
+    fn pulse_bounded_and_periodic() {
+        let (period, amp) = (1.7, 0.2);
+        for i in 0..1000 {
+            let t = i as f64 * 0.013;
+            let s = pulse_scale(t, period, amp);
+            assert!((1.0 - amp..=1.0 + amp).contains(&s));
+            assert!((s - pulse_scale(t + period, period, amp)).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn eccentricity_vector_points_to_periapsis() {
+        let r = 200.0;
+        let v_circular = (PULL / r).sqrt();
+        let dir = dvec2(0.6, -0.8);
+
+        // too slow for a circular orbit, so we're at apoapsis and periapsis is opposite
+        let e = eccentricity_vector(dir * r, dir.perp() * v_circular * 0.8, PULL);
+        assert!((e.length() - 0.36).abs() < 1e-9);
+        assert!((e.normalize() + dir).length() < 1e-9);
+
+        // too fast, so we're at periapsis
+        let e = eccentricity_vector(dir * r, dir.perp() * v_circular * 1.2, PULL);
+        assert!((e.length() - 0.44).abs() < 1e-9);
+        assert!((e.normalize() - dir).length() < 1e-9);
+    }
+
+    #[test]
+    fn clip_polyline_cuts_at_bound() {
+        let bounds = Rect {
+            min: dvec2(-10.0, -10.0),
+            max: dvec2(10.0, 10.0),
+        };
+        let path = [
+            dvec2(0.0, 0.0),
+            dvec2(5.0, 5.0),
+            dvec2(15.0, 7.5),
+            dvec2(5.0, 5.0),
+        ];
+        assert_eq!(
+            clip_polyline(&path, bounds),
+            [dvec2(0.0, 0.0), dvec2(5.0, 5.0), dvec2(10.0, 6.25)]
+        );
+        assert_eq!(clip_polyline(&path[..2], bounds), path[..2]);
+        assert!(clip_polyline(&path[2..], bounds).is_empty());
+    }
+
+    #[test]
+    fn initial_orbit_is_bound() {
+        let sat = starting_orbit();
+        // starting at 0.8 of circular speed puts us at apoapsis with e = 1 - 0.8^2
+        let e = eccentricity_vector(sat.pos, sat.vel, PULL).length();
+        assert!((e - 0.36).abs() < 1e-6);
+    }
+
+    #[test]
+    fn initial_orbit_must_clear_horizon() {
+        let radius = Config::default().start_radius;
+        // too low
+        assert!(Sat::initial_orbit(EVENT_HORIZON_METERS * 0.9, 0.8, PULL).is_err());
+        // too eccentric
+        assert!(Sat::initial_orbit(radius, 0.3, PULL).is_err());
+        // circular, just outside
+        assert!(Sat::initial_orbit(EVENT_HORIZON_METERS * 1.1, 1.0, PULL).is_ok());
+    }
+
+    #[test]
+    fn eccentricity_and_semi_major_axis_agree_with_path() {
+        let r = 200.0;
+        let v_circular = (PULL / r).sqrt();
+        for (dir, vel) in [
+            (dvec2(1.0, 0.0), dvec2(0.0, 0.8)),
+            (dvec2(0.0, -1.0), dvec2(1.1, 0.0)),
+            (dvec2(0.6, 0.8), dvec2(-0.5, 0.7)),
+            (dvec2(-0.8, 0.6), dvec2(0.3, 0.9)),
+        ] {
+            let sat = Sat::new(dir * r, vel * v_circular, 0.0);
+            let e_vec = eccentricity_vector(sat.pos, sat.vel, PULL);
+            assert!((sat.eccentricity(PULL) - e_vec.length()).abs() < 1e-9);
+
+            // periapsis and apoapsis radii sum to the major axis
+            let a = sat.semi_major_axis(PULL);
+            let period = std::f64::consts::TAU * (a * a * a / PULL).sqrt();
+            let radii = integrate_collect(sat, period, 0.0001, IntegratorKind::Verlet)
+                .iter()
+                .map(|s| s.pos.length())
+                .collect_vec();
+            let (min, max) = radii.iter().copied().minmax().into_option().unwrap();
+            assert!(((min + max) / 2.0 - a).abs() < a * 1e-3, "{min} {max} {a}");
+            assert!(((max - min) / (max + min) - e_vec.length()).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn random_points_are_on_circle() {
+        for _ in 0..10_000 {
+            let p = random_point_on_circle(WORLD_RADIUS_METERS);
+            assert!(p.is_finite());
+            assert!((p.length() - WORLD_RADIUS_METERS).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn apsis_line_passes_through_focus_at_argument_of_periapsis() {
+        let r = 200.0;
+        let v_circular = (PULL / r).sqrt();
+        let ap = 2.0_f64;
+        let dir = DVec2::from_angle(ap);
+        let sat = Sat::new(dir * r, dir.perp() * v_circular * 1.2, 0.0);
+
+        let (peri, apo) = apsis_line(&sat, PULL);
+        let apo = apo.unwrap();
+        assert!((peri - sat.pos).length() < 1e-9);
+        assert!((peri.y.atan2(peri.x) - ap).abs() < 1e-9);
+        // collinear with, and on opposite sides of, the focus
+        assert!(peri.perp_dot(apo).abs() < 1e-6 * peri.length() * apo.length());
+        assert!(peri.dot(apo) < 0.0);
+        assert!((apo.length() - r * (1.0 + 0.44) / (1.0 - 0.44)).abs() < 1e-6);
+
+        let escaping = Sat {
+            vel: sat.vel * 2.0,
+            ..sat
+        };
+        let (peri, apo) = apsis_line(&escaping, PULL);
+        assert!((peri - sat.pos).length() < 1e-9);
+        assert!(apo.is_none());
+    }
+
+    #[test]
+    fn schwarzschild_radius_of_sun() {
+        // about 2.95 km for one solar mass
+        let r = schwarzschild_radius(1.989e30);
+        assert!((r - 2953.0).abs() < 5.0, "{r}");
+    }
+
+    #[test]
+    fn compensated_sum_beats_naive() {
+        let (dt, n) = (0.001, 10_000_000);
+        let exact = dt * n as f64;
+        let mut naive = 0.0;
+        let mut compensated = CompensatedSum::new(0.0);
+        for _ in 0..n {
+            naive += dt;
+            compensated.add(dt);
+        }
+        let naive_err = (naive - exact).abs();
+        let compensated_err = (compensated.total() - exact).abs();
+        assert!(
+            compensated_err < naive_err / 1000.0,
+            "{compensated_err} {naive_err}"
+        );
+    }
+
+    #[cfg(feature = "compensated-time")]
+    #[test]
+    fn compensated_time_holds_across_frames() {
+        let mut sat = starting_orbit();
+        let mut steps = 0u64;
+        // about three hours of frames
+        for _ in 0..600_000 {
+            let frame_end = sat.when + 1.0 / 60.0;
+            sat.tick_to_with(
+                frame_end,
+                IntegratorKind::Euler,
+                Gravity::Point(PULL),
+                |_| steps += 1,
+            );
+        }
+        let exact = steps as f64 * TICK;
+        assert!((sat.when - exact).abs() < 1e-9, "{} {exact}", sat.when);
+    }
+
+    #[test]
+    fn focus_cycle_wraps() {
+        let mut focused = None;
+        let mut seen = vec![];
+        for _ in 0..5 {
+            focused = cycle_focus(focused, 4);
+            seen.push(focused.unwrap());
+        }
+        assert_eq!(seen, [0, 1, 2, 3, 0]);
+        assert_eq!(cycle_focus(None, 0), None);
+
+        let view = build_view(dvec2(800.0, 600.0), 1.0, dvec2(300.0, -200.0), None);
+        assert_eq!(view.project(dvec2(300.0, -200.0)), dvec2(400.0, 300.0));
+    }
+
+    #[test]
+    fn dilation_factor_bounds_and_monotonic() {
+        let horizon = EVENT_HORIZON_METERS;
+        let mut last = 0.0;
+        for i in 0..1000 {
+            let f = dilation_factor(i as f64, horizon);
+            assert!(f > 0.0 && f <= 1.0);
+            assert!(f >= last);
+            last = f;
+        }
+        assert!(dilation_factor(0.0, horizon) < 0.01);
+        assert!(dilation_factor(-10.0, horizon) > 0.0);
+        assert!(dilation_factor(1e9, horizon) > 0.99);
+    }
+
+    #[test]
+    fn substeps_follow_integrated_path() {
+        let initial = starting_orbit();
+        let mut sat = initial;
+        let mut substeps = vec![];
+        sat.tick_to_with(0.05, IntegratorKind::Euler, Gravity::Point(PULL), |s| {
+            substeps.push(*s)
+        });
+
+        let expected = integrate_collect(initial, 0.05, 0.001, IntegratorKind::Euler);
+        assert!(substeps.len() >= 49);
+        for (got, want) in substeps.iter().zip(&expected[1..]) {
+            assert_eq!(got.pos, want.pos);
+            assert_eq!(got.vel, want.vel);
+        }
+        assert_eq!(substeps.last().unwrap().pos, sat.pos);
+    }
+
+    #[test]
+    fn assisted_warp_endpoints() {
+        let start = starting_orbit();
+        let new_pos = dvec2(0.0, -150.0);
+
+        let mut raw = start;
+        assisted_warp(&mut raw, new_pos, 0.0, PULL);
+        assert_eq!(raw.pos, new_pos);
+        assert_eq!(raw.vel, start.vel);
+
+        let mut corrected = start;
+        assisted_warp(&mut corrected, new_pos, 1.0, PULL);
+        assert_eq!(corrected.pos, new_pos);
+        let energy = |s: &Sat| s.specific_energy(PULL);
+        assert!((energy(&corrected) - energy(&start)).abs() < 1e-6 * energy(&start).abs());
+        // velocity keeps its angle to the position
+        let angle = |s: &Sat| s.pos.angle_between(s.vel);
+        assert!((angle(&corrected) - angle(&start)).abs() < 1e-9);
+
+        // warping inward can't keep energy without diving into the horizon, so circularize
+        let mut safe = Sat {
+            vel: dvec2(-300.0, 100.0),
+            ..start
+        };
+        assisted_warp(&mut safe, dvec2(60.0, 0.0), 1.0, PULL);
+        assert!(safe.periapsis(PULL).length() > EVENT_HORIZON_METERS);
+        assert!(safe.eccentricity(PULL) < 1e-6);
+
+        let mut half = start;
+        assisted_warp(&mut half, new_pos, 0.5, PULL);
+        assert!((half.vel - (start.vel + corrected.vel) / 2.0).length() < 1e-9);
+    }
+
+    #[test]
+    fn log_view_round_trips() {
+        let affine = build_view(dvec2(1280.0, 720.0), 1.0, DVec2::ZERO, None).affine;
+        let r0 = EVENT_HORIZON_METERS;
+        for p in [
+            dvec2(0.0, 0.0),
+            dvec2(1.0, 0.0),
+            dvec2(-30.0, 40.0),
+            dvec2(700.0, -900.0),
+            dvec2(1e5, 3.0),
+        ] {
+            let screen = world_to_screen_log(p, &affine, r0);
+            let back = screen_to_world_log(screen, &affine, r0);
+            assert!(
+                (back - p).length() < 1e-9 * p.length().max(1.0),
+                "{p} {back}"
+            );
+        }
+
+        // the world edge stays put, while the horizon is pushed outward to be visible
+        let edge = dvec2(WORLD_RADIUS_METERS, 0.0);
+        assert!((log_radial(edge, r0) - edge).length() < 1e-9);
+        assert!(log_radial(dvec2(r0, 0.0), r0).x > 4.0 * r0);
+    }
+
+    #[test]
+    fn warp_damping_scales_speed() {
+        let config = Config {
+            warp_damping: 0.9,
+            ..Config::default()
+        };
+        let start = starting_orbit();
+        let mut sat = start;
+        let mut warp_point = WarpPoint {
+            pos: dvec2(0.0, -150.0),
+            color: Color::new(1.0, 1.0, 1.0, 1.0),
+            win_destination: DVec2::ZERO,
+        };
+        do_warp(&mut sat, &mut warp_point, &config);
+        assert_eq!(sat.pos, dvec2(0.0, -150.0));
+        assert_eq!(warp_point.pos, start.pos);
+        assert!((sat.vel.length() - 0.9 * start.vel.length()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn no_gravity_coasts_in_a_straight_line() {
+        let config = Config {
+            no_gravity: true,
+            ..Config::default()
+        };
+        let start = Sat::new(dvec2(200.0, 0.0), dvec2(-30.0, 40.0), 0.0);
+        for integrator in [IntegratorKind::Euler, IntegratorKind::Verlet] {
+            let mut sat = start;
+            sat.tick_to(2.0, integrator, config.gravity());
+            assert_eq!(sat.vel, start.vel);
+            assert!((sat.pos - (start.pos + start.vel * sat.when)).length() < 1e-9);
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "gravitational parameter must be positive")]
+    fn non_positive_mu_is_rejected() {
+        let sat = Sat::new(dvec2(200.0, 0.0), DVec2::ZERO, 0.0);
+        sat.acceleration(Gravity::Point(0.0));
+    }
+
+    #[test]
+    fn single_steps_match_tick_to() {
+        let initial = starting_orbit();
+        for integrator in [IntegratorKind::Euler, IntegratorKind::Verlet] {
+            let n = 250;
+            let mut stepped = initial;
+            for _ in 0..n {
+                stepped.step(TICK, integrator, Gravity::Point(PULL));
+            }
+            let mut ticked = initial;
+            ticked.tick_to(n as f64 * TICK, integrator, Gravity::Point(PULL));
+            assert_eq!(stepped.pos, ticked.pos);
+            assert_eq!(stepped.vel, ticked.vel);
+        }
+    }
+
+    #[test]
+    fn energy_normalized_to_circular() {
+        let r = 200.0;
+        let circular = Sat::new(dvec2(r, 0.0), dvec2(0.0, (PULL / r).sqrt()), 0.0);
+        let text = format_energy(circular.specific_energy(PULL), r, PULL);
+        assert!(text.ends_with("(-1.00 circular)"), "{text}");
+
+        let escape_speed = (2.0 * PULL / r).sqrt();
+        let nearly_escaping = Sat {
+            vel: dvec2(0.0, 0.9999 * escape_speed),
+            ..circular
+        };
+        let text = format_energy(nearly_escaping.specific_energy(PULL), r, PULL);
+        assert!(text.ends_with("(-0.00 circular)"), "{text}");
+        let text = format_energy(0.0, r, PULL);
+        assert!(text.ends_with("(0.00 circular)"), "{text}");
+    }
+
+    #[test]
+    fn eject_lands_on_safe_circular_orbit() {
+        let config = Config::default();
+        let diving = Sat::new(dvec2(80.0, 0.0), dvec2(-500.0, 10.0), 12.0);
+        let sat = eject(&diving, &config, PULL).unwrap();
+        assert_eq!(sat.when, diving.when);
+        assert!(sat.specific_energy(PULL) < 0.0);
+        assert!(sat.eccentricity(PULL) < 1e-9);
+        assert!(sat.periapsis(PULL).length() > EVENT_HORIZON_METERS);
+        assert!((sat.pos.length() - config.start_radius).abs() < 1e-9);
+
+        assert!(eject_ready(None, 0.0));
+        assert!(!eject_ready(Some(12.0), 12.0 + EJECT_COOLDOWN / 2.0));
+        assert!(eject_ready(Some(12.0), 12.0 + EJECT_COOLDOWN));
+    }
+
+    #[test]
+    fn dropped_circular_particle_stays_circular() {
+        let r = 200.0;
+        let from = dvec2(r, 0.0);
+        let to = from + dvec2(0.0, (PULL / r).sqrt() / DROP_SPEED_PER_METER);
+        let mut particles = Vec::new();
+        drop_test_particle(&mut particles, from, to, 0.0);
+        let period = std::f64::consts::TAU * (r * r * r / PULL).sqrt();
+        let mut sat = particles[0];
+        while sat.when < period {
+            sat.tick_to(sat.when + 0.1, IntegratorKind::Verlet, Gravity::Point(PULL));
+            assert!((sat.pos.length() - r).abs() < r * 1e-3, "{sat:?}");
+        }
+
+        for _ in 0..MAX_TEST_PARTICLES {
+            drop_test_particle(&mut particles, to, from, 0.0);
+        }
+        assert_eq!(particles.len(), MAX_TEST_PARTICLES);
+        assert!(particles.iter().all(|p| p.pos == to));
+    }
+
+    #[test]
+    fn sample_count_scales_with_arc() {
+        assert_eq!(adaptive_sample_count(0.0, 16, 256), 16);
+        assert_eq!(adaptive_sample_count(f64::NAN, 16, 256), 16);
+        assert_eq!(adaptive_sample_count(f64::INFINITY, 16, 256), 256);
+        assert_eq!(adaptive_sample_count(1e6, 16, 256), 256);
+        let counts = (0..400)
+            .map(|px| adaptive_sample_count(px as f64 * 10.0, 16, 256))
+            .collect_vec();
+        assert!(counts.windows(2).all(|w| w[0] <= w[1]));
+        assert!(counts.contains(&100));
+    }
+
+    #[test]
+    fn launch_must_not_fall_back() {
+        let escape = 2f64.sqrt();
+        let straight_up = Launch {
+            angle: std::f64::consts::FRAC_PI_2,
+            speed: 1.01 * escape,
+        };
+        let sat = straight_up.orbit(PULL).unwrap();
+        assert!((sat.pos.length() - Launch::RADIUS).abs() < 1e-12);
+        assert!(sat.pos.dot(sat.vel) > 0.0);
+
+        let horizontal = Launch {
+            angle: 0.0,
+            speed: 1.2,
+        };
+        let sat = horizontal.orbit(PULL).unwrap();
+        assert!((sat.periapsis(PULL).length() - Launch::RADIUS).abs() < 1e-6);
+        assert_eq!(
+            horizon::crossing_time(&sat, PULL, EVENT_HORIZON_METERS),
+            Crossing::Never
+        );
+
+        for launch in [
+            Launch {
+                speed: 0.9 * escape,
+                ..straight_up
+            },
+            Launch {
+                angle: 0.3,
+                speed: 1.2,
+            },
+            Launch {
+                speed: 0.9,
+                ..horizontal
+            },
+        ] {
+            let err = launch.orbit(PULL).unwrap_err();
+            assert!(err.contains("falls back"), "{launch:?} {err}");
+        }
+    }
+
+    #[test]
+    fn pinned_orbit_stays_put() {
+        let palette = ThemeColors::default().orbit_palette;
+        let first = Sat::new(dvec2(-150.0, 100.0), dvec2(-200.0, -300.0), 0.0);
+        // the distances to the two foci of an ellipse always sum to 2a
+        let on_orbit = |sat: &Sat, p: DVec2| {
+            let a = sat.semi_major_axis(PULL);
+            let other_focus = -2.0 * a * eccentricity_vector(sat.pos, sat.vel, PULL);
+            (p.length() + p.distance(other_focus) - 2.0 * a).abs() < 1e-6 * a
+        };
+        let mut pins = Vec::new();
+        pin_orbit(&mut pins, first, PULL, &palette);
+        assert_eq!(pins[0].sat, first);
+        assert!(pins[0].path.iter().all(|p| on_orbit(&first, *p)));
+
+        // pinning the live orbit as it moves on and changes leaves the first pin alone
+        let mut live = first;
+        for _ in 1..MAX_PINNED {
+            live.tick_to(
+                live.when + 0.5,
+                IntegratorKind::Verlet,
+                Gravity::Point(PULL),
+            );
+            live.vel *= 1.05;
+            pin_orbit(&mut pins, live, PULL, &palette);
+        }
+        assert_eq!(pins.len(), MAX_PINNED);
+        assert!(pins[0].path.iter().all(|p| on_orbit(&first, *p)));
+        assert!(!pins[1].path.iter().all(|p| on_orbit(&first, *p)));
+
+        // every pin has its own color, which stays put when the oldest is forgotten
+        let colors = pins.iter().map(|p| p.color).collect_vec();
+        assert!(colors.iter().tuple_combinations().all(|(a, b)| a != b));
+        pin_orbit(&mut pins, live, PULL, &palette);
+        assert_eq!(pins.len(), MAX_PINNED);
+        assert_eq!(
+            pins.iter().map(|p| p.color).collect_vec()[..MAX_PINNED - 1],
+            colors[1..]
+        );
+        assert_eq!(pins.last().unwrap().color, colors[0]);
+    }
+
+    #[test]
+    fn render_interpolation_endpoints() {
+        let prev = starting_orbit();
+        let mut cur = prev;
+        cur.step(TICK, IntegratorKind::Verlet, Gravity::Point(PULL));
+        assert_eq!(interpolate_sat(&prev, &cur, 0.0), prev.pos);
+        assert_eq!(interpolate_sat(&prev, &cur, 1.0), cur.pos);
+
+        assert_eq!(render_alpha(&prev, &cur, prev.when), 0.0);
+        assert_eq!(render_alpha(&prev, &cur, cur.when), 1.0);
+        assert!((render_alpha(&prev, &cur, prev.when + TICK / 4.0) - 0.25).abs() < 1e-9);
+        assert_eq!(render_alpha(&cur, &cur, 5.0), 1.0);
+    }
+
+    #[test]
+    fn legend_follows_overlays() {
+        let labels = |config: &Config, any_pinned| {
+            legend(config, any_pinned, false)
+                .into_iter()
+                .map(|(label, _)| label)
+                .collect_vec()
+        };
+        let mut config = Config::default();
+        assert_eq!(
+            labels(&config, false),
+            ["player", "predicted path", "warp capture radius"]
+        );
+        config.warp_assist = 1.0;
+        assert!(!labels(&config, false).contains(&"warp capture radius"));
+        assert!(legend(&config, false, true)
+            .iter()
+            .any(|(label, _)| *label == "path after warping"));
+
+        config.show_apsis_line = true;
+        assert!(labels(&config, false).contains(&"line of apsides"));
+        let entry = legend(&config, false, false)
+            .into_iter()
+            .find(|(label, _)| *label == "line of apsides");
+        assert_eq!(entry.unwrap().1, config.theme.apsis_line);
+
+        assert!(labels(&config, true).contains(&"pinned orbits"));
+
+        config.show_apsis_line = false;
+        assert!(!labels(&config, false).contains(&"line of apsides"));
+    }
+
+    #[test]
+    fn photo_mode_freezes_sim_but_not_camera() {
+        let mut clock = SimClock::default();
+        let mut sat = starting_orbit();
+        sat.tick_to(clock.now(1.0), IntegratorKind::Verlet, Gravity::Point(PULL));
+
+        let mut photo = PhotoMode::enter(DVec2::ZERO, clock.paused());
+        clock.pause(1.0);
+        let frozen = sat;
+        for wall in [1.5, 2.0, 10.0] {
+            sat.tick_to(
+                clock.now(wall),
+                IntegratorKind::Verlet,
+                Gravity::Point(PULL),
+            );
+            photo.pan(dvec2(10.0, 0.0));
+            photo.zoom_by(2.0);
+        }
+        assert_eq!(sat, frozen);
+        assert_eq!(photo.center, dvec2(30.0, 0.0));
+        assert_eq!(photo.zoom, 8.0);
+
+        // leaving picks up from the frozen time, not the wall clock
+        assert!(photo.resume_on_exit);
+        clock.resume(10.0);
+        assert_eq!(clock.now(10.5), 1.5);
+
+        let view = build_view(dvec2(800.0, 600.0), 1.0, photo.center, None);
+        let zoomed = view.zoomed(dvec2(800.0, 600.0), photo.zoom);
+        assert!((zoomed.project(photo.center) - dvec2(400.0, 300.0)).length() < 1e-9);
+        let p = dvec2(50.0, -20.0);
+        assert!((zoomed.unproject(zoomed.project(p)) - p).length() < 1e-9);
+    }
+
+    #[test]
+    fn flight_path_angle_through_an_orbit() {
+        let initial = starting_orbit();
+        assert!(initial.eccentricity(PULL) > 0.1);
+        let a = initial.semi_major_axis(PULL);
+        let period = std::f64::consts::TAU * (a * a * a / PULL).sqrt();
+        let states = integrate_collect(initial, period, TICK, IntegratorKind::Verlet);
+
+        let (peri, apo) = states
+            .iter()
+            .minmax_by(|a, b| a.pos.length().total_cmp(&b.pos.length()))
+            .into_option()
+            .unwrap();
+        assert!(peri.flight_path_angle().abs() < 0.01, "{peri:?}");
+        assert!(apo.flight_path_angle().abs() < 0.01, "{apo:?}");
+
+        for s in &states {
+            let outbound = s.pos.dot(s.vel);
+            if outbound.abs() > 1e-3 * s.pos.length() * s.vel.length() {
+                assert_eq!(s.flight_path_angle() > 0.0, outbound > 0.0, "{s:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn thinning_keeps_ends_and_order() {
+        let path = (0..1001).map(|i| dvec2(i as f64, 0.0)).collect_vec();
+        let thin = thin_polyline(&path, 16);
+        assert_eq!(thin.len(), 16);
+        assert_eq!(thin[0], path[0]);
+        assert_eq!(thin[15], path[1000]);
+        assert!(thin.windows(2).all(|w| w[0].x < w[1].x));
+        assert_eq!(thin_polyline(&path[..10], 16), &path[..10]);
+    }
+
+    #[test]
+    fn warp_preview_matches_warp() {
+        let config = Config {
+            warp_assist: 0.5,
+            warp_damping: 0.9,
+            ..Config::default()
+        };
+        let mut sat = starting_orbit();
+        let mut warp_point = WarpPoint {
+            pos: dvec2(0.0, -150.0),
+            color: Color::new(1.0, 1.0, 1.0, 1.0),
+            win_destination: DVec2::ZERO,
+        };
+        let preview = preview_warp(&sat, &warp_point, &config);
+        assert_eq!(
+            warp_point.pos,
+            dvec2(0.0, -150.0),
+            "previewing doesn't warp"
+        );
+        do_warp(&mut sat, &mut warp_point, &config);
+        assert_eq!(preview, sat);
+    }
+
+    #[test]
+    fn prediction_runs_forward() {
+        let sat = starting_orbit();
+        let path = predicted_path(sat, IntegratorKind::Verlet, Gravity::Point(PULL), None);
+        let mut later = sat;
+        later.tick_to(0.5, IntegratorKind::Verlet, Gravity::Point(PULL));
+        assert_eq!(path[0], sat.pos);
+        assert!(path.contains(&later.pos));
+    }
+
+    #[test]
+    fn unlimited_prediction_runs_past_the_limit() {
+        // heading straight out along +x, fast enough to pass the limit within the predicted second
+        let sat = Sat::new(dvec2(1000.0, 0.0), dvec2(2000.0, 0.0), 0.0);
+        let limit = 1500.0;
+        let clipped = predicted_path(
+            sat,
+            IntegratorKind::Verlet,
+            Gravity::Point(PULL),
+            Some(limit),
+        );
+        assert!(clipped
+            .iter()
+            .all(|p| p.abs().max_element() <= limit + 1e-9));
+        // cut off right where it crosses the limit
+        assert!((clipped.last().unwrap().x - limit).abs() < 1e-9);
+
+        let full = predicted_path(sat, IntegratorKind::Verlet, Gravity::Point(PULL), None);
+        assert!(full.last().unwrap().x > limit);
+        assert!(full.iter().all(|p| p.y.abs() < 1e-9));
+    }
+
+    #[test]
+    fn verlet_circular_orbit_keeps_radius() {
+        let r = WORLD_RADIUS_METERS / 6.0;
+        let initial = Sat::new(dvec2(r, 0.0), dvec2(0.0, (PULL / r).sqrt()), 0.0);
+        let period = std::f64::consts::TAU * r / initial.vel.length();
+        let states = integrate_collect(initial, period, 0.001, IntegratorKind::Verlet);
+        assert!(states.len() > 1000);
+        for sat in states {
+            assert!((sat.pos.length() - r).abs() < r * 1e-3, "{sat:?}");
+        }
     }
 }
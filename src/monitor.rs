@@ -0,0 +1,152 @@
+use std::collections::VecDeque;
+
+use glam::DVec2;
+
+/// Standard gravity, for expressing acceleration in g.
+pub const STANDARD_GRAVITY: f64 = 9.80665;
+
+/// Tracks the largest acceleration experienced, and whether it broke `limit`.
+#[derive(Clone, Debug, Default)]
+pub struct GForceMonitor {
+    peak: f64,
+    /// Acceleration, in g, beyond which the ship breaks up.
+    limit: Option<f64>,
+}
+
+impl GForceMonitor {
+    pub fn new(limit: Option<f64>) -> Self {
+        Self { peak: 0.0, limit }
+    }
+
+    pub fn sample(&mut self, acc: DVec2) {
+        self.peak = self.peak.max(acc.length() / STANDARD_GRAVITY);
+    }
+
+    /// Largest acceleration seen so far, in g.
+    pub fn peak(&self) -> f64 {
+        self.peak
+    }
+
+    pub fn exceeded(&self) -> bool {
+        self.limit.is_some_and(|limit| self.peak > limit)
+    }
+}
+
+/// Extremes of the player's distance from the black hole.
+#[derive(Clone, Debug)]
+pub struct OrbitStats {
+    pub min_radius: f64,
+    pub max_radius: f64,
+}
+
+impl Default for OrbitStats {
+    fn default() -> Self {
+        Self {
+            min_radius: f64::INFINITY,
+            max_radius: 0.0,
+        }
+    }
+}
+
+impl OrbitStats {
+    pub fn sample(&mut self, pos: DVec2) {
+        let r = pos.length();
+        self.min_radius = self.min_radius.min(r);
+        self.max_radius = self.max_radius.max(r);
+    }
+}
+
+/// The player's speed over the last `window` seconds.
+#[derive(Clone, Debug)]
+pub struct SpeedHistory {
+    /// `(time, speed)`, oldest first.
+    pub samples: VecDeque<(f64, f64)>,
+    window: f64,
+}
+
+impl SpeedHistory {
+    pub fn new(window: f64) -> Self {
+        Self {
+            samples: VecDeque::new(),
+            window,
+        }
+    }
+
+    pub fn push(&mut self, time: f64, speed: f64) {
+        self.samples.push_back((time, speed));
+        while self
+            .samples
+            .front()
+            .is_some_and(|&(t, _)| t < time - self.window)
+        {
+            self.samples.pop_front();
+        }
+    }
+
+    pub fn current(&self) -> Option<f64> {
+        self.samples.back().map(|&(_, s)| s)
+    }
+
+    pub fn min(&self) -> Option<f64> {
+        self.samples.iter().map(|&(_, s)| s).reduce(f64::min)
+    }
+
+    pub fn max(&self) -> Option<f64> {
+        self.samples.iter().map(|&(_, s)| s).reduce(f64::max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::dvec2;
+
+    #[test]
+    fn records_peak_and_trips_limit() {
+        let mut monitor = GForceMonitor::new(Some(3.0));
+        for g in [1.0, 2.5, 0.5] {
+            monitor.sample(dvec2(0.0, -g * STANDARD_GRAVITY));
+        }
+        assert!((monitor.peak() - 2.5).abs() < 1e-12);
+        assert!(!monitor.exceeded());
+
+        monitor.sample(dvec2(3.0, 0.5) * STANDARD_GRAVITY);
+        assert!(monitor.peak() > 3.0);
+        assert!(monitor.exceeded());
+
+        monitor.sample(DVec2::ZERO);
+        assert!(monitor.exceeded(), "the peak is sticky");
+
+        let mut unlimited = GForceMonitor::new(None);
+        unlimited.sample(dvec2(1e9, 0.0));
+        assert!(!unlimited.exceeded());
+    }
+
+    #[test]
+    fn orbit_stats_tracks_extremes() {
+        let mut stats = OrbitStats::default();
+        for p in [dvec2(3.0, 4.0), dvec2(0.0, -2.0), dvec2(10.0, 0.0)] {
+            stats.sample(p);
+        }
+        assert_eq!(stats.min_radius, 2.0);
+        assert_eq!(stats.max_radius, 10.0);
+    }
+
+    #[test]
+    fn speed_history_rolls() {
+        let mut history = SpeedHistory::new(2.0);
+        assert_eq!(history.current(), None);
+        for (t, speed) in [(0.0, 5.0), (1.0, 9.0), (2.0, 7.0), (3.0, 6.0)] {
+            history.push(t, speed);
+        }
+        // the sample from t = 0 is more than 2 seconds old
+        assert_eq!(history.samples.len(), 3);
+        assert_eq!(history.current(), Some(6.0));
+        assert_eq!(history.min(), Some(6.0));
+        assert_eq!(history.max(), Some(9.0));
+
+        history.push(10.0, 1.0);
+        assert_eq!(history.samples.len(), 1);
+        assert_eq!(history.max(), Some(1.0));
+    }
+}
@@ -0,0 +1,91 @@
+use std::collections::VecDeque;
+
+use glam::DVec2;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Particle {
+    pub pos: DVec2,
+    pub vel: DVec2,
+    /// Seconds since spawning.
+    pub age: f64,
+}
+
+/// Short-lived cosmetic particles drifting in straight lines, oldest dropped first when full.
+#[derive(Clone, Debug)]
+pub struct ParticleSystem {
+    particles: VecDeque<Particle>,
+    capacity: usize,
+    /// Seconds each particle lives.
+    lifetime: f64,
+}
+
+impl ParticleSystem {
+    pub fn new(capacity: usize, lifetime: f64) -> Self {
+        Self {
+            particles: VecDeque::with_capacity(capacity),
+            capacity,
+            lifetime,
+        }
+    }
+
+    pub fn spawn(&mut self, pos: DVec2, vel: DVec2) {
+        if self.particles.len() == self.capacity {
+            self.particles.pop_front();
+        }
+        self.particles.push_back(Particle { pos, vel, age: 0.0 });
+    }
+
+    pub fn step(&mut self, dt: f64) {
+        for p in &mut self.particles {
+            p.pos += p.vel * dt;
+            p.age += dt;
+        }
+        // spawned in order, so the expired ones are all at the front
+        while self
+            .particles
+            .front()
+            .is_some_and(|p| p.age >= self.lifetime)
+        {
+            self.particles.pop_front();
+        }
+    }
+
+    /// Living particles with how much of their life remains, from 1 when new toward 0.
+    pub fn alive(&self) -> impl Iterator<Item = (&Particle, f64)> {
+        self.particles
+            .iter()
+            .map(|p| (p, 1.0 - p.age / self.lifetime))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::dvec2;
+
+    #[test]
+    fn particles_expire() {
+        let mut system = ParticleSystem::new(16, 1.0);
+        system.spawn(DVec2::ZERO, dvec2(2.0, 0.0));
+        system.step(0.5);
+        system.spawn(DVec2::ZERO, DVec2::ZERO);
+        let (first, life) = system.alive().next().unwrap();
+        assert_eq!(first.pos, dvec2(1.0, 0.0));
+        assert!((life - 0.5).abs() < 1e-12);
+
+        system.step(0.6);
+        assert_eq!(system.alive().count(), 1);
+        system.step(0.6);
+        assert_eq!(system.alive().count(), 0);
+    }
+
+    #[test]
+    fn particle_count_is_capped() {
+        let mut system = ParticleSystem::new(3, 10.0);
+        for i in 0..5 {
+            system.spawn(DVec2::splat(i as f64), DVec2::ZERO);
+        }
+        let positions: Vec<_> = system.alive().map(|(p, _)| p.pos.x).collect();
+        assert_eq!(positions, [2.0, 3.0, 4.0]);
+    }
+}
@@ -0,0 +1,102 @@
+use std::f64::consts::{PI, TAU};
+
+use glam::DVec2;
+
+use crate::eccentricity_vector;
+use crate::horizon::{time_since_periapsis, true_anomaly, NEAR_PARABOLIC};
+use crate::Sat;
+
+/// Where `epoch` would be at `when` coasting exactly along its ellipse, free of the integrator's
+/// error. `None` for unbound, nearly parabolic, or radial orbits.
+pub fn analytic_position(epoch: &Sat, mu: f64, when: f64) -> Option<DVec2> {
+    let h = epoch.pos.perp_dot(epoch.vel);
+    let e_vec = eccentricity_vector(epoch.pos, epoch.vel, mu);
+    let e = e_vec.length();
+    if e >= 1.0 - NEAR_PARABOLIC || h == 0.0 {
+        return None;
+    }
+    let p = h * h / mu;
+    let a = p / (1.0 - e * e);
+    let mean_motion = (mu / (a * a * a)).sqrt();
+    let since_periapsis = time_since_periapsis(true_anomaly(epoch.pos, epoch.vel, e_vec), e, p, mu);
+    let mean_anomaly = ((since_periapsis + when - epoch.when) * mean_motion).rem_euclid(TAU);
+
+    // Newton's method on Kepler's equation, M = E - e sin E
+    let mut ecc_anomaly = if e < 0.8 { mean_anomaly } else { PI };
+    for _ in 0..50 {
+        let delta =
+            (ecc_anomaly - e * ecc_anomaly.sin() - mean_anomaly) / (1.0 - e * ecc_anomaly.cos());
+        ecc_anomaly -= delta;
+        if delta.abs() < 1e-14 {
+            break;
+        }
+    }
+    let nu = 2.0
+        * ((1.0 + e).sqrt() * (ecc_anomaly / 2.0).sin())
+            .atan2((1.0 - e).sqrt() * (ecc_anomaly / 2.0).cos());
+    let r = p / (1.0 + e * nu.cos());
+
+    // true anomaly is measured from periapsis in the direction of motion
+    let periapsis_dir = e_vec.try_normalize().unwrap_or(epoch.pos.normalize());
+    let angle = nu.copysign(h);
+    Some(DVec2::from_angle(angle).rotate(periapsis_dir) * r)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Gravity, IntegratorKind, PULL};
+    use glam::dvec2;
+
+    fn shadow_error(epoch: Sat, periods: f64) -> f64 {
+        let r = epoch.pos.length();
+        let period = TAU * (r * r * r / PULL).sqrt();
+        let mut sat = epoch;
+        let mut worst: f64 = 0.0;
+        while sat.when < periods * period {
+            sat.tick_to(
+                sat.when + 0.05,
+                IntegratorKind::Verlet,
+                Gravity::Point(PULL),
+            );
+            let analytic = analytic_position(&epoch, PULL, sat.when).unwrap();
+            worst = worst.max(analytic.distance(sat.pos));
+        }
+        worst
+    }
+
+    #[test]
+    fn circular_shadow_matches_verlet() {
+        let r = 200.0;
+        let circular = Sat::new(dvec2(r, 0.0), dvec2(0.0, (PULL / r).sqrt()), 0.0);
+        assert!(shadow_error(circular, 1.0) < 0.05);
+
+        let clockwise = Sat {
+            vel: -circular.vel,
+            ..circular
+        };
+        assert!(shadow_error(clockwise, 1.0) < 0.05);
+    }
+
+    #[test]
+    fn eccentric_shadow_matches_verlet() {
+        let sat = Sat::new(dvec2(-150.0, 100.0), dvec2(-200.0, -300.0), 3.0);
+        assert!(sat.specific_energy(PULL) < 0.0);
+        let start = analytic_position(&sat, PULL, sat.when).unwrap();
+        assert!(start.distance(sat.pos) < 1e-4, "{start} {sat:?}");
+
+        let mut stepped = sat;
+        stepped.tick_to(sat.when + 0.5, IntegratorKind::Verlet, Gravity::Point(PULL));
+        let analytic = analytic_position(&sat, PULL, stepped.when).unwrap();
+        assert!(
+            analytic.distance(stepped.pos) < 0.05,
+            "{analytic} {stepped:?}"
+        );
+
+        let escaping = Sat {
+            vel: sat.vel * 10.0,
+            ..sat
+        };
+        assert_eq!(analytic_position(&escaping, PULL, 4.0), None);
+    }
+}
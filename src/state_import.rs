@@ -0,0 +1,93 @@
+use glam::{dvec2, DVec2};
+
+use crate::Sat;
+
+/// Read a planar state vector from csv and scale it into the game world.
+///
+/// The csv holds one row of `mu,x,y,vx,vy` in SI units (m³/s², m, m/s) describing an orbit
+/// around some other central body, e.g. exported from an ephemeris. An optional header row and
+/// `#` comments are skipped. The orbit is scaled so its apoapsis (or current distance, if it is
+/// unbound) lands at `fit_radius`, and velocity is scaled so the orbit keeps its shape under
+/// the game's `mu`.
+pub fn import_state(text: &str, mu: f64, fit_radius: f64, horizon: f64) -> Result<Sat, String> {
+    let row = text
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .find(|l| !l.starts_with(|c: char| c.is_ascii_alphabetic()))
+        .ok_or("no state vector row found")?;
+    let fields = row
+        .split(',')
+        .map(|f| f.trim().parse::<f64>().map_err(|e| format!("{f:?}: {e}")))
+        .collect::<Result<Vec<_>, _>>()?;
+    let [source_mu, x, y, vx, vy] = fields[..] else {
+        return Err(format!(
+            "expected 5 columns (mu,x,y,vx,vy), got {}",
+            fields.len()
+        ));
+    };
+    if !fields.iter().all(|f| f.is_finite()) {
+        return Err("state vector has non-finite values".into());
+    }
+    if source_mu <= 0.0 {
+        return Err(format!("mu must be positive, got {source_mu}"));
+    }
+    let (pos, vel) = (dvec2(x, y), dvec2(vx, vy));
+    if pos == DVec2::ZERO {
+        return Err("position is at the central body".into());
+    }
+
+    let source = Sat::new(pos, vel, 0.0);
+    let extent = source
+        .apoapsis(source_mu)
+        .map_or(pos.length(), |apo| apo.length());
+    // v²r/mu is invariant for orbits of the same shape
+    let length_scale = fit_radius / extent;
+    let speed_scale = (mu / source_mu / length_scale).sqrt();
+    let sat = Sat::new(pos * length_scale, vel * speed_scale, 0.0);
+
+    let periapsis = sat.periapsis(mu).length();
+    if periapsis <= horizon {
+        return Err(format!(
+            "periapsis of {periapsis:.1} m after scaling is inside the {horizon} m event horizon"
+        ));
+    }
+    Ok(sat)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{eccentricity_vector, EVENT_HORIZON_METERS, PULL};
+
+    const EARTH_MU: f64 = 3.986e14;
+
+    #[test]
+    fn imports_molniya_perigee() {
+        // perigee of a molniya orbit: 6900 km perigee radius, 26600 km semi-major axis
+        let (r_p, a) = (6.9e6, 2.66e7);
+        let v_p = (EARTH_MU * (2.0 / r_p - 1.0 / a)).sqrt();
+        let text = format!("mu,x,y,vx,vy\n{EARTH_MU},{r_p},0,0,{v_p}\n");
+
+        let sat = import_state(&text, PULL, 512.0, EVENT_HORIZON_METERS).unwrap();
+
+        let e = eccentricity_vector(sat.pos, sat.vel, PULL);
+        assert!((e.length() - (1.0 - r_p / a)).abs() < 1e-9);
+        assert!(e.x > 0.0 && e.y.abs() < 1e-9);
+        assert!((sat.apoapsis(PULL).unwrap().length() - 512.0).abs() < 1e-6);
+        let expected_periapsis = r_p * 512.0 / (2.0 * a - r_p);
+        assert!((sat.periapsis(PULL).length() - expected_periapsis).abs() < 1e-6);
+    }
+
+    #[test]
+    fn rejects_bad_states() {
+        let dives_in = format!("{EARTH_MU},7e6,0,0,1000");
+        let err = import_state(&dives_in, PULL, 512.0, EVENT_HORIZON_METERS).unwrap_err();
+        assert!(err.contains("horizon"), "{err}");
+
+        assert!(import_state("1,2,3", PULL, 512.0, EVENT_HORIZON_METERS).is_err());
+        assert!(import_state("1,0,0,1,1", PULL, 512.0, EVENT_HORIZON_METERS).is_err());
+        assert!(import_state("-1,1,0,0,1", PULL, 512.0, EVENT_HORIZON_METERS).is_err());
+        assert!(import_state("1,x,0,0,1", PULL, 512.0, EVENT_HORIZON_METERS).is_err());
+    }
+}
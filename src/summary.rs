@@ -0,0 +1,116 @@
+use crate::monitor::{GForceMonitor, OrbitStats};
+
+/// Where the end-of-run summary is written.
+pub const SUMMARY_PATH: &str = "run-summary.json";
+
+/// What happened over one run, from start to win or loss.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RunSummary {
+    pub outcome: String,
+    /// Seconds from start to end.
+    pub duration: f64,
+    pub warps: usize,
+    pub peak_g: f64,
+    /// Smallest distance, in meters, between the player and the event horizon.
+    pub closest_horizon_approach: f64,
+    pub min_radius: f64,
+    pub max_radius: f64,
+}
+
+impl RunSummary {
+    pub fn from_monitors(
+        outcome: &str,
+        duration: f64,
+        warps: usize,
+        g_force: &GForceMonitor,
+        orbit: &OrbitStats,
+        horizon: f64,
+    ) -> Self {
+        Self {
+            outcome: outcome.to_string(),
+            duration,
+            warps,
+            peak_g: g_force.peak(),
+            closest_horizon_approach: (orbit.min_radius - horizon).max(0.0),
+            min_radius: orbit.min_radius,
+            max_radius: orbit.max_radius,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        let outcome = self.outcome.replace('\\', "\\\\").replace('"', "\\\"");
+        format!(
+            "{{\n  \"outcome\": \"{outcome}\",\n  \"duration\": {},\n  \"warps\": {},\n  \
+             \"peak_g\": {},\n  \"closest_horizon_approach\": {},\n  \"min_radius\": {},\n  \
+             \"max_radius\": {}\n}}\n",
+            json_number(self.duration),
+            self.warps,
+            json_number(self.peak_g),
+            json_number(self.closest_horizon_approach),
+            json_number(self.min_radius),
+            json_number(self.max_radius),
+        )
+    }
+}
+
+/// JSON has no infinity or NaN, so those become `null`.
+fn json_number(x: f64) -> String {
+    if x.is_finite() {
+        x.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitor::STANDARD_GRAVITY;
+    use glam::dvec2;
+
+    #[test]
+    fn assembles_from_monitors() {
+        let mut g_force = GForceMonitor::new(None);
+        g_force.sample(dvec2(0.0, 12.0 * STANDARD_GRAVITY));
+        let mut orbit = OrbitStats::default();
+        orbit.sample(dvec2(80.0, 0.0));
+        orbit.sample(dvec2(0.0, 300.0));
+
+        let summary = RunSummary::from_monitors("fell in", 42.5, 3, &g_force, &orbit, 50.0);
+        assert_eq!(
+            summary,
+            RunSummary {
+                outcome: "fell in".into(),
+                duration: 42.5,
+                warps: 3,
+                peak_g: 12.0,
+                closest_horizon_approach: 30.0,
+                min_radius: 80.0,
+                max_radius: 300.0,
+            }
+        );
+        assert_eq!(
+            summary.to_json(),
+            "{\n  \"outcome\": \"fell in\",\n  \"duration\": 42.5,\n  \"warps\": 3,\n  \
+             \"peak_g\": 12,\n  \"closest_horizon_approach\": 30,\n  \"min_radius\": 80,\n  \
+             \"max_radius\": 300\n}\n"
+        );
+    }
+
+    #[test]
+    fn non_finite_fields_are_null() {
+        // lost before the first sample, so the closest approach is still infinite
+        let summary = RunSummary::from_monitors(
+            "fell in",
+            0.0,
+            0,
+            &GForceMonitor::new(None),
+            &OrbitStats::default(),
+            50.0,
+        );
+        let json = summary.to_json();
+        assert!(json.contains("\"min_radius\": null"));
+        assert!(json.contains("\"closest_horizon_approach\": null"));
+        assert!(!json.contains("inf"));
+    }
+}
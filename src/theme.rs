@@ -0,0 +1,168 @@
+use macroquad::color::{colors, hsl_to_rgb, Color};
+
+/// Every color the game draws with.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ThemeColors {
+    pub background: Color,
+    pub black_hole: Color,
+    pub player: Color,
+    pub prediction: Color,
+    pub warp_palette: [Color; 4],
+    /// Cycled through by orbit number, for the trail and pinned orbits.
+    pub orbit_palette: [Color; 6],
+    pub eccentricity: Color,
+    pub angular_momentum: Color,
+    pub hud: Color,
+    pub apsis_line: Color,
+    pub schwarzschild_radius: Color,
+    pub danger: Color,
+    pub substeps: Color,
+    pub chord: Color,
+    pub speed_graph: Color,
+    pub comet_tail: Color,
+    pub test_particle: Color,
+    pub shadow: Color,
+}
+
+impl Default for ThemeColors {
+    fn default() -> Self {
+        Self {
+            background: colors::BLACK,
+            black_hole: colors::VIOLET,
+            player: colors::YELLOW,
+            prediction: colors::ORANGE,
+            warp_palette: [
+                colors::DARKBROWN,
+                colors::MAROON,
+                colors::DARKBLUE,
+                colors::BEIGE,
+            ],
+            // evenly around the color wheel
+            orbit_palette: std::array::from_fn(|i| hsl_to_rgb(i as f32 / 6.0, 0.8, 0.6)),
+            eccentricity: colors::SKYBLUE,
+            angular_momentum: colors::PINK,
+            hud: colors::LIGHTGRAY,
+            apsis_line: colors::GREEN,
+            schwarzschild_radius: colors::WHITE,
+            danger: colors::RED,
+            substeps: colors::LIME,
+            chord: colors::MAGENTA,
+            speed_graph: colors::GOLD,
+            comet_tail: colors::VIOLET,
+            test_particle: colors::WHITE,
+            shadow: colors::GRAY,
+        }
+    }
+}
+
+impl ThemeColors {
+    /// A stable color for the `index`th orbit.
+    pub fn orbit_color(&self, index: usize) -> Color {
+        self.orbit_palette[index % self.orbit_palette.len()]
+    }
+
+    /// Parse `name = #rrggbb` (or `#rrggbbaa`) lines on top of the default theme. Blank lines and
+    /// lines starting with `#` are ignored.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut ret = Self::default();
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (name, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("expected `name = #rrggbb`, got {line:?}"))?;
+            let color = parse_color(value.trim())?;
+            let slot = match name.trim() {
+                "background" => &mut ret.background,
+                "black_hole" => &mut ret.black_hole,
+                "player" => &mut ret.player,
+                "prediction" => &mut ret.prediction,
+                "warp0" => &mut ret.warp_palette[0],
+                "warp1" => &mut ret.warp_palette[1],
+                "warp2" => &mut ret.warp_palette[2],
+                "warp3" => &mut ret.warp_palette[3],
+                "orbit0" => &mut ret.orbit_palette[0],
+                "orbit1" => &mut ret.orbit_palette[1],
+                "orbit2" => &mut ret.orbit_palette[2],
+                "orbit3" => &mut ret.orbit_palette[3],
+                "orbit4" => &mut ret.orbit_palette[4],
+                "orbit5" => &mut ret.orbit_palette[5],
+                "eccentricity" => &mut ret.eccentricity,
+                "angular_momentum" => &mut ret.angular_momentum,
+                "hud" => &mut ret.hud,
+                "apsis_line" => &mut ret.apsis_line,
+                "schwarzschild_radius" => &mut ret.schwarzschild_radius,
+                "danger" => &mut ret.danger,
+                "substeps" => &mut ret.substeps,
+                "chord" => &mut ret.chord,
+                "speed_graph" => &mut ret.speed_graph,
+                "comet_tail" => &mut ret.comet_tail,
+                "test_particle" => &mut ret.test_particle,
+                "shadow" => &mut ret.shadow,
+                other => return Err(format!("unknown theme color {other:?}")),
+            };
+            *slot = color;
+        }
+        Ok(ret)
+    }
+}
+
+fn parse_color(s: &str) -> Result<Color, String> {
+    let hex = s
+        .strip_prefix('#')
+        .filter(|h| h.len() == 6 || h.len() == 8)
+        .ok_or_else(|| format!("expected #rrggbb or #rrggbbaa, got {s:?}"))?;
+    let channel = |i: usize| {
+        hex.get(i..i + 2)
+            .and_then(|c| u8::from_str_radix(c, 16).ok())
+            .ok_or_else(|| format!("invalid hex color {s:?}"))
+    };
+    let a = if hex.len() == 8 { channel(6)? } else { 255 };
+    Ok(Color::from_rgba(channel(0)?, channel(2)?, channel(4)?, a))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_player_and_prediction_differ() {
+        let theme = ThemeColors::default();
+        assert_ne!(theme.player, theme.prediction);
+    }
+
+    #[test]
+    fn consecutive_orbit_colors_differ() {
+        let theme = ThemeColors::default();
+        for i in 0..20 {
+            let (a, b) = (
+                theme.orbit_color(i).to_vec(),
+                theme.orbit_color(i + 1).to_vec(),
+            );
+            assert!((a - b).length() > 0.3, "{i}");
+        }
+        assert_eq!(
+            theme.orbit_color(2),
+            theme.orbit_color(2 + theme.orbit_palette.len())
+        );
+    }
+
+    #[test]
+    fn parse_overrides_defaults() {
+        let theme = ThemeColors::parse(
+            "# my theme\n\
+             player = #ff0000\n\
+             prediction = #00ff0080\n\
+             orbit1 = #0000ff\n",
+        )
+        .unwrap();
+        assert_eq!(theme.player, Color::from_rgba(255, 0, 0, 255));
+        assert_eq!(theme.prediction, Color::from_rgba(0, 255, 0, 128));
+        assert_eq!(theme.orbit_color(1), Color::from_rgba(0, 0, 255, 255));
+        assert_eq!(theme.background, ThemeColors::default().background);
+
+        assert!(ThemeColors::parse("player = red").is_err());
+        assert!(ThemeColors::parse("sun = #ffffff").is_err());
+    }
+}
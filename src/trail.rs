@@ -0,0 +1,134 @@
+use std::collections::VecDeque;
+
+use glam::DVec2;
+
+/// Counts full laps around the black hole by accumulating the angle swept out.
+#[derive(Clone, Debug, Default)]
+pub struct OrbitCounter {
+    pub completed: usize,
+    swept: f64,
+    last_angle: Option<f64>,
+}
+
+impl OrbitCounter {
+    pub fn observe(&mut self, pos: DVec2) {
+        let angle = pos.y.atan2(pos.x);
+        if let Some(last) = self.last_angle {
+            // wrap into [-pi, pi) so crossing the negative x axis isn't a full turn
+            let delta = (angle - last + std::f64::consts::PI).rem_euclid(std::f64::consts::TAU)
+                - std::f64::consts::PI;
+            self.swept += delta;
+            if self.swept.abs() >= std::f64::consts::TAU {
+                self.completed += 1;
+                self.swept -= std::f64::consts::TAU.copysign(self.swept);
+            }
+        }
+        self.last_angle = Some(angle);
+    }
+
+    /// Forget the partial lap, so a jump to a new position isn't counted as angle swept.
+    pub fn teleported(&mut self) {
+        self.swept = 0.0;
+        self.last_angle = None;
+    }
+}
+
+/// Recent player positions, each tagged with the orbit it was recorded on.
+#[derive(Clone, Debug)]
+pub struct Trail {
+    pub points: VecDeque<(DVec2, usize)>,
+    capacity: usize,
+    /// Simulated seconds between kept points, so feeding every substep doesn't shorten the trail.
+    spacing: f64,
+    last_when: Option<f64>,
+}
+
+impl Trail {
+    pub fn new(capacity: usize, spacing: f64) -> Self {
+        Self {
+            points: VecDeque::with_capacity(capacity),
+            capacity,
+            spacing,
+            last_when: None,
+        }
+    }
+
+    /// Record the player at `pos` at time `when`, unless the last point is more recent than
+    /// `spacing`.
+    pub fn push(&mut self, pos: DVec2, orbit: usize, when: f64) {
+        if self
+            .last_when
+            .is_some_and(|last| when - last < self.spacing)
+        {
+            return;
+        }
+        self.last_when = Some(when);
+        if self.points.len() == self.capacity {
+            self.points.pop_front();
+        }
+        self.points.push_back((pos, orbit));
+    }
+
+    pub fn clear(&mut self) {
+        self.points.clear();
+        self.last_when = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_laps() {
+        let mut counter = OrbitCounter::default();
+        for i in 0..=250 {
+            let angle = i as f64 / 100.0 * std::f64::consts::TAU;
+            counter.observe(DVec2::from_angle(angle) * 100.0);
+        }
+        assert_eq!(counter.completed, 2);
+
+        // clockwise laps count too
+        let mut counter = OrbitCounter::default();
+        for i in 0..=150 {
+            let angle = -(i as f64) / 100.0 * std::f64::consts::TAU;
+            counter.observe(DVec2::from_angle(angle) * 100.0);
+        }
+        assert_eq!(counter.completed, 1);
+    }
+
+    #[test]
+    fn teleport_sweeps_no_angle() {
+        let mut counter = OrbitCounter::default();
+        for i in 0..=90 {
+            let angle = i as f64 / 100.0 * std::f64::consts::TAU;
+            counter.observe(DVec2::from_angle(angle) * 100.0);
+        }
+        // jumping back to the start of the lap must not finish it
+        counter.teleported();
+        for i in 0..=20 {
+            let angle = i as f64 / 100.0 * std::f64::consts::TAU;
+            counter.observe(DVec2::from_angle(angle) * 100.0);
+        }
+        assert_eq!(counter.completed, 0);
+    }
+
+    #[test]
+    fn trail_is_bounded() {
+        let mut trail = Trail::new(3, 0.0);
+        for i in 0..5 {
+            trail.push(DVec2::splat(i as f64), 0, i as f64);
+        }
+        assert_eq!(trail.points.len(), 3);
+        assert_eq!(trail.points[0].0, DVec2::splat(2.0));
+    }
+
+    #[test]
+    fn trail_keeps_one_point_per_spacing() {
+        let mut trail = Trail::new(100, 1.0);
+        for i in 0..=40 {
+            trail.push(DVec2::splat(i as f64), 0, i as f64 * 0.25);
+        }
+        assert_eq!(trail.points.len(), 11);
+    }
+}